@@ -23,14 +23,15 @@ fn bench_encrypt(c: &mut Criterion) {
         .map(|j| lagrange_poly(n, j))
         .collect();
 
+    let rt = tokio::runtime::Runtime::new().unwrap();
     for i in 0..n {
         sk.push(SecretKey::<E>::new(&mut rng));
-        pk.push(sk[i].get_pk(0, &params, n, &lagrange_polys))
+        pk.push(rt.block_on(sk[i].get_pk(0, &params, n, &lagrange_polys)).unwrap())
     }
 
-    let ak = AggregateKey::<E>::new(pk, n, &params);
+    let ak = AggregateKey::<E>::new(pk, n, &params).unwrap();
 
-    c.bench_function("encrypt", |b| b.iter(|| encrypt::<E>(&ak, t, &params)));
+    c.bench_function("encrypt", |b| b.iter(|| encrypt::<E>(&ak, t, &params).unwrap()));
 }
 
 criterion_group!(benches, bench_encrypt);
@@ -9,11 +9,14 @@ use clap::Parser;
 use serde::{Deserialize, Serialize};
 use hex::{self, ToHex};
 
+use ark_bls12_377::Bls12_377;
 use ark_bls12_381::{Bls12_381, G1Affine, G2Affine};
+use ark_bn254::Bn254;
 use ark_ec::{bls12::Bls12, pairing::Pairing};
 use ark_std::{rand::Rng, Zero};
 use rand::{rngs::OsRng, RngCore};
 use silent_threshold::{
+    curve::{read_file, write_file, CurveId},
     decryption::{agg_dec, is_valid, part_verify},
     encryption::encrypt,
     kzg::{UniversalParams, KZG10},
@@ -41,9 +44,17 @@ struct Args {
     /// Comittee size
     #[arg(short)]
     n: usize,
+
+    /// Curve of the transcript, bls12-381, bls12-377 or bn254
+    #[arg(long, default_value = "bls12-381")]
+    curve: String,
+
+    /// Path of the transcript
+    #[arg(long, default_value = "transcript-512")]
+    transcript: String,
 }
 
-fn is_equal(pk1: &PublicKey<E>, pk2: &PublicKey<E>) -> bool {
+fn is_equal<E: Pairing>(pk1: &PublicKey<E>, pk2: &PublicKey<E>) -> bool {
     println!("pk1.bls_pk == pk2.bls_pk: {}", pk1.bls_pk == pk2.bls_pk);
     println!("pk1.id == pk2.id: {}", pk1.id == pk2.id);
     println!("pk1.sk_li == pk2.sk_li: {}", pk1.sk_li == pk2.sk_li);
@@ -56,55 +67,46 @@ fn is_equal(pk1: &PublicKey<E>, pk2: &PublicKey<E>) -> bool {
     pk1.bls_pk == pk2.bls_pk && pk1.id == pk2.id && pk1.sk_li == pk2.sk_li && pk1.sk_li_by_tau == pk2.sk_li_by_tau && pk1.sk_li_by_z == pk2.sk_li_by_z && pk1.sk_li_minus0 == pk2.sk_li_minus0
 }
 
-#[tokio::main]
-async fn main() {
-    let args = Args::parse();
-
-    if args.n != 0 && (args.n & (args.n - 1)) != 0 {
-        panic!("n should be a power of two");
-    }
-
-    let lagrange_polys: Vec<DensePolynomial<<Bls12<ark_bls12_381::Config> as Pairing>::ScalarField>> = (0..args.n)
-        .map(|j| lagrange_poly(args.n, j))
+async fn create_helpers<E: CurveId>(n: usize, transcript: &str) {
+    let lagrange_polys: Vec<DensePolynomial<E::ScalarField>> = (0..n)
+        .map(|j| lagrange_poly(n, j))
         .collect();
 
-    let mut file = File::open("transcript-512").await.expect("Can't open the file!");
+    let mut file = File::open(transcript).await.expect("Can't open the file!");
     let mut contents = Vec::new();
     let _ = file.read_to_end(&mut contents).await;
     println!("{}", contents.len());
-    let cur = Cursor::new(contents);
-    let kzg_setup = UniversalParams::<E>::deserialize_compressed(cur).unwrap();
+    let kzg_setup = read_file::<E, UniversalParams<E>>(&contents).unwrap();
     drop(file);
 
     let mut rng = OsRng;
     let sk = SecretKey::<E>::new(&mut rng);
     let t = time::Instant::now();
-    let lagrange_helper = LagrangePolyHelper::new(&sk, args.n, &kzg_setup).await;
+    let lagrange_helper = LagrangePolyHelper::new(&sk, n, &kzg_setup).await.unwrap();
     println!("Elapsed: {:#?}", t.elapsed());
 
     let t = time::Instant::now();
-    let pk = sk.get_pk(0, &kzg_setup, args.n, &lagrange_polys).await;
+    let pk = sk.get_pk(0, &kzg_setup, n, &lagrange_polys).await.unwrap();
     println!("elapsed for normal pk: {:#?}", t.elapsed());
     
     let t = time::Instant::now();
-    let pk_exp = get_pk_exp(&sk, 0, args.n, &lagrange_helper);
+    let pk_exp = get_pk_exp(&sk, 0, n, &lagrange_helper).unwrap();
     println!("elapsed for experimental pk: {:#?}", t.elapsed());
 
-/*     let t = time::Instant::now();
-    let is_valid_helper = IsValidHelper::new(args.n).await;
+    let t = time::Instant::now();
+    let is_valid_helper = IsValidHelper::new(n, &kzg_setup).await.unwrap();
     println!("Elapsed: {:#?}", t.elapsed());
 
-    println!("{}", is_valid(&pk, args.n, &kzg_setup, &is_valid_helper).await);
-    println!("{}", is_valid(&pk_exp, args.n, &kzg_setup, &is_valid_helper).await); */
+    println!("{}", is_valid(&pk, n, &kzg_setup, &is_valid_helper).await);
+    println!("{}", is_valid(&pk_exp, n, &kzg_setup, &is_valid_helper).await);
     println!("{}", is_equal(&pk, &pk_exp));
 
     if !Path::new("./lagrangehelpers").exists() {
         fs::create_dir("./lagrangehelpers").unwrap();
     }
 
-    let mut file = File::create(format!("./lagrangehelpers/{}", args.n)).await.expect("Can't open the file");
-    let mut wr = Vec::new();
-    lagrange_helper.serialize_compressed(&mut wr).unwrap();
+    let mut file = File::create(format!("./lagrangehelpers/{}", n)).await.expect("Can't open the file");
+    let wr = write_file::<E, _>(&lagrange_helper);
     file.write_all(&wr).await.expect("Can't write to the file!");
 
 
@@ -112,8 +114,23 @@ async fn main() {
         fs::create_dir("./isvalidhelpers").unwrap();
     }
 
-/*     let mut file = File::create(format!("./isvalidhelpers/{}", args.n)).await.expect("Can't open the file");
-    let mut wr = Vec::new();
-    is_valid_helper.serialize_compressed(&mut wr).unwrap();
-    file.write_all(&wr).await.expect("Can't write to the file!"); */
-}
\ No newline at end of file
+    let mut file = File::create(format!("./isvalidhelpers/{}", n)).await.expect("Can't open the file");
+    let wr = write_file::<E, _>(&is_valid_helper);
+    file.write_all(&wr).await.expect("Can't write to the file!");
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    if args.n != 0 && (args.n & (args.n - 1)) != 0 {
+        panic!("n should be a power of two");
+    }
+
+    match args.curve.as_str() {
+        Bls12_381::NAME => create_helpers::<Bls12_381>(args.n, &args.transcript).await,
+        Bls12_377::NAME => create_helpers::<Bls12_377>(args.n, &args.transcript).await,
+        Bn254::NAME => create_helpers::<Bn254>(args.n, &args.transcript).await,
+        curve => panic!("unknown curve {}", curve),
+    }
+}
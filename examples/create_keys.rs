@@ -1,17 +1,17 @@
 use core::panic;
-use std::{fs, io::Cursor, path::Path, time};
+use std::{fs, path::Path, time};
 
 use ark_serialize::Read;
 use clap::Parser;
 
+use ark_bls12_377::Bls12_377;
 use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
 use rand::{rngs::OsRng, RngCore};
 use silent_threshold::{
-    setup::{get_pk_exp, SecretKey}, utils::LagrangePolyHelper
+    curve::{read_file, write_file, CurveId}, setup::{get_pk_exp, SecretKey}, utils::LagrangePolyHelper
 };
 
-type E = Bls12_381;
-
 use std::fs::File;
 use ark_serialize::*;
 
@@ -25,32 +25,24 @@ struct Args {
     /// Key count
     #[arg(short)]
     k: usize,
-}
-
-#[tokio::main]
-async fn main() {
-    let args = Args::parse();
 
-    if args.n != 0 && (args.n & (args.n - 1)) != 0 {
-        panic!("n should be a power of two");
-    }
-
-    if args.n <= args.k {
-        panic!("n can't be equal to or less than k")
-    }
+    /// Curve of the keys, bls12-381, bls12-377 or bn254
+    #[arg(long, default_value = "bls12-381")]
+    curve: String,
+}
 
-    let mut file = File::open(format!("./lagrangehelpers/{}", args.n)).unwrap();
+fn create_keys<E: CurveId>(n: usize, k: usize) {
+    let mut file = File::open(format!("./lagrangehelpers/{}", n)).unwrap();
     let mut contents = Vec::new();
     let _ = file.read_to_end(&mut contents);
-    let cur = Cursor::new(contents);
-    let lagrange_helper = LagrangePolyHelper::deserialize_compressed(cur).unwrap();
+    let lagrange_helper = read_file::<E, LagrangePolyHelper<E>>(&contents).unwrap();
     drop(file);
 
     if !Path::new("./keys").exists() {
         fs::create_dir("./keys").unwrap();
     }
 
-    for i in 0..args.k {
+    for i in 0..k {
         let sk_filename = format!("keys/{}-bls", i + 1);
         let ecdsa_filename = format!("keys/{}-ecdsa", i + 1);
 
@@ -60,8 +52,7 @@ async fn main() {
         let sk = SecretKey::<E>::new(&mut rng);
         // Write the secret key to file
         let mut sk_file = File::create(sk_filename).expect("Can't open the file!");
-        let mut sk_wr = Vec::new();
-        sk.serialize_compressed(&mut sk_wr).unwrap();
+        let sk_wr = write_file::<E, _>(&sk);
         sk_file.write_all(&sk_wr).expect("Can't write to the file!");
 
         // Generate ECDSA key bytes and serialize
@@ -75,14 +66,32 @@ async fn main() {
         ecdsa_file.write_all(&ecdsa_wr).expect("Can't write to the file!");
         
         let t = time::Instant::now();
-        let pk = get_pk_exp(&sk, i + 1, args.n, &lagrange_helper);
+        let pk = get_pk_exp(&sk, i + 1, n, &lagrange_helper).unwrap();
         println!("{}-pk: {:#?}", pk.id, t.elapsed());
-        let mut pk_wr = Vec::new();
         
         let pk_filename = format!("keys/{}-pk", pk.id);
         let mut pk_file = File::create(pk_filename).expect("Can't write to the file!");
-        pk.serialize_compressed(&mut pk_wr).unwrap();
+        let pk_wr = write_file::<E, _>(&pk);
         pk_file.write_all(&pk_wr).expect("Can't write to the file!");
     }
-    
-}
\ No newline at end of file
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    if args.n != 0 && (args.n & (args.n - 1)) != 0 {
+        panic!("n should be a power of two");
+    }
+
+    if args.n <= args.k {
+        panic!("n can't be equal to or less than k")
+    }
+
+    match args.curve.as_str() {
+        Bls12_381::NAME => create_keys::<Bls12_381>(args.n, args.k),
+        Bls12_377::NAME => create_keys::<Bls12_377>(args.n, args.k),
+        Bn254::NAME => create_keys::<Bn254>(args.n, args.k),
+        curve => panic!("unknown curve {}", curve),
+    }
+}
@@ -3,13 +3,11 @@ use core::panic;
 use ark_poly::univariate::DensePolynomial;
 use clap::Parser;
 
+use ark_bls12_377::Bls12_377;
 use ark_bls12_381::Bls12_381;
-use ark_ec::pairing::Pairing;
+use ark_bn254::Bn254;
 use rand::rngs::OsRng;
-use silent_threshold::kzg::{UniversalParams, KZG10};
-
-type E = Bls12_381;
-type UniPoly381 = DensePolynomial<<E as Pairing>::ScalarField>;
+use silent_threshold::{curve::{write_file, CurveId}, kzg::{UniversalParams, KZG10}};
 
 use std::fs::File;
 use ark_serialize::*;
@@ -20,6 +18,21 @@ struct Args {
     /// Comittee size
     #[arg(short)]
     n: usize,
+
+    /// Curve of the transcript, bls12-381, bls12-377 or bn254
+    #[arg(long, default_value = "bls12-381")]
+    curve: String,
+}
+
+fn create_transcript<E: CurveId>(n: usize) {
+    let mut rng = OsRng;
+    let kzg_setup: UniversalParams<E> = KZG10::<E, DensePolynomial<E::ScalarField>>::setup(n, &mut rng).unwrap();
+
+    let mut file = File::create(format!("transcript-{}", n)).expect("Can't open the file!");
+    let wr = write_file::<E, _>(&kzg_setup);
+    file.write_all(&wr).expect("Can't write to the file!");
+
+    println!("powers_of_g: {}, powers_of_h: {}", kzg_setup.powers_of_g.len(), kzg_setup.powers_of_h.len());
 }
 
 fn main() {
@@ -33,13 +46,10 @@ fn main() {
         panic!("can't use 512 for comittee size");
     }
 
-    let mut rng = OsRng;
-    let kzg_setup: UniversalParams<E> = KZG10::<E, UniPoly381>::setup(args.n, &mut rng).unwrap();
-
-    let mut file = File::create(format!("transcript-{}", args.n)).expect("Can't open the file!");
-    let mut wr = Vec::new();
-    kzg_setup.clone().serialize_compressed(&mut wr).unwrap();
-    file.write_all(&wr).expect("Can't write to the file!");
-
-    println!("powers_of_g: {}, powers_of_h: {}", kzg_setup.powers_of_g.len(), kzg_setup.powers_of_h.len());
-}
\ No newline at end of file
+    match args.curve.as_str() {
+        Bls12_381::NAME => create_transcript::<Bls12_381>(args.n),
+        Bls12_377::NAME => create_transcript::<Bls12_377>(args.n),
+        Bn254::NAME => create_transcript::<Bn254>(args.n),
+        curve => panic!("unknown curve {}", curve),
+    }
+}
@@ -1,5 +1,5 @@
 use core::panic;
-use std::{io::Cursor, time};
+use std::time;
 
 use ark_serialize::Read;
 use clap::Parser;
@@ -9,6 +9,7 @@ use ark_ec::pairing::Pairing;
 use ark_std::Zero;
 use rand::rngs::OsRng;
 use silent_threshold::{
+    curve::read_file,
     decryption::agg_dec,
     encryption::encrypt,
     kzg::UniversalParams,
@@ -20,7 +21,6 @@ type G2 = <E as Pairing>::G2;
 use tokio;
 
 use std::fs::File;
-use ark_serialize::*;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -62,15 +62,13 @@ async fn main() {
     let mut file = File::open(format!("./lagrangehelpers/{}", n)).unwrap();
     let mut contents = Vec::new();
     let _ = file.read_to_end(&mut contents);
-    let cur = Cursor::new(contents);
-    let lagrange_helper = LagrangePolyHelper::deserialize_compressed(cur).unwrap();
+    let lagrange_helper = read_file::<E, LagrangePolyHelper<E>>(&contents).unwrap();
     drop(file);
  
     let mut file = File::open("transcript-512").unwrap();
     let mut contents = Vec::new();
     let _ = file.read_to_end(&mut contents);
-    let cur = Cursor::new(contents);
-    let params = UniversalParams::deserialize_compressed(cur).unwrap();
+    let params = read_file::<E, UniversalParams<E>>(&contents).unwrap();
     drop(file);
 
     let mut sk = Vec::new();
@@ -78,28 +76,26 @@ async fn main() {
 
     sk.push(SecretKey::<E>::new(&mut rng));
     sk[0].nullify();
-    pk.push(get_pk_exp(&sk[0], 0, n, &lagrange_helper));
+    pk.push(get_pk_exp(&sk[0], 0, n, &lagrange_helper).unwrap());
 
     for i in 1..k {
         let ti = time::Instant::now();
         let mut file = File::open(format!("keys/{}-pk", i)).unwrap();
         let mut contents = Vec::new();
         let _ = file.read_to_end(&mut contents);
-        let cur = Cursor::new(contents);
-        let key = PublicKey::<E>::deserialize_compressed(cur).unwrap();
+        let key = read_file::<E, PublicKey<E>>(&contents).unwrap();
         pk.push(key);
         
         let mut file = File::open(format!("keys/{}-bls", i)).unwrap();
         let mut contents = Vec::new();
         let _ = file.read_to_end(&mut contents);
-        let cur = Cursor::new(contents);
-        let key = SecretKey::<E>::deserialize_compressed(cur).unwrap();
+        let key = read_file::<E, SecretKey<E>>(&contents).unwrap();
         sk.push(key);
         println!("{}: {:#?}", i, ti.elapsed());
     }
 
-    let agg_key = AggregateKey::<E>::new(pk.clone(), n, &params);
-    let ct = encrypt::<E>(&agg_key, t, &params);
+    let agg_key = AggregateKey::<E>::new(pk.clone(), n, &params).unwrap();
+    let ct = encrypt::<E>(&agg_key, t, &params).unwrap();
 
     // compute partial decryptions
     let mut partial_decryptions: Vec<G2> = Vec::new();
@@ -121,7 +117,7 @@ async fn main() {
     }
 
     let ti = time::Instant::now();
-    let _dec_key = agg_dec(&partial_decryptions, &ct.sa1, &ct.sa2, t, n, &selector, &agg_key, &params).await;
+    let _dec_key = agg_dec(&partial_decryptions, &ct.sa1, &ct.sa2, t, n, &selector, &agg_key, &params).await.unwrap();
     println!("{:#?}: elapsed time for decryption", ti.elapsed());
 
     println!("{}", _dec_key == ct.enc_key);
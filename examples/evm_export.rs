@@ -0,0 +1,60 @@
+use ark_bn254::Bn254;
+use ark_ec::pairing::Pairing;
+use ark_poly::univariate::DensePolynomial;
+use ark_std::Zero;
+use rand::rngs::OsRng;
+use silent_threshold::{
+    decryption::agg_dec,
+    encryption::encrypt,
+    evm::{export_header, export_part_dec, pairing_check, part_verify_calldata},
+    kzg::{UniversalParams, KZG10},
+    setup::{get_pk_exp, AggregateKey, PublicKey, SecretKey},
+    utils::LagrangePolyHelper,
+};
+
+type E = Bn254;
+type G2 = <E as Pairing>::G2;
+type UniPoly254 = DensePolynomial<<E as Pairing>::ScalarField>;
+
+#[tokio::main]
+async fn main() {
+    let n = 8; // actually n-1 total parties. one party is a dummy party that is always true
+    let t: usize = 4;
+
+    let mut rng = OsRng;
+    let params: UniversalParams<E> = KZG10::<E, UniPoly254>::setup(n, &mut rng).unwrap();
+
+    let mut sk: Vec<SecretKey<E>> = Vec::new();
+    sk.push(SecretKey::<E>::new(&mut rng));
+    sk[0].nullify();
+    for _ in 1..n {
+        sk.push(SecretKey::<E>::new(&mut rng));
+    }
+
+    let lagrange_helper = LagrangePolyHelper::<E>::new(&sk[0], n, &params).await.unwrap();
+    let pk: Vec<PublicKey<E>> = (0..n).map(|i| get_pk_exp(&sk[i], i, n, &lagrange_helper).unwrap()).collect();
+
+    let agg_key = AggregateKey::<E>::new(pk.clone(), n, &params).unwrap();
+    let ct = encrypt::<E>(&agg_key, t, &params).unwrap();
+    println!("header: 0x{}", hex::encode(export_header(&ct)));
+
+    let mut partial_decryptions: Vec<G2> = Vec::new();
+    let mut selector: Vec<bool> = Vec::new();
+    for i in 0..n {
+        if i <= t {
+            let part_dec = sk[i].partial_decryption(ct.gamma_g2);
+            let calldata = part_verify_calldata(&ct.gamma_g2, &pk[i].bls_pk, &part_dec);
+            println!("part_dec {}: 0x{}", i, hex::encode(export_part_dec(&part_dec)));
+            println!("part_verify {} calldata: 0x{} ({:?})", i, hex::encode(&calldata), pairing_check(&calldata));
+
+            partial_decryptions.push(part_dec);
+            selector.push(true);
+        } else {
+            partial_decryptions.push(G2::zero());
+            selector.push(false);
+        }
+    }
+
+    let dec_key = agg_dec(&partial_decryptions, &ct.sa1, &ct.sa2, t, n, &selector, &agg_key, &params).await.unwrap();
+    println!("{}", dec_key == ct.enc_key);
+}
@@ -29,19 +29,19 @@ async fn main() {
 
     sk.push(SecretKey::<E>::new(&mut rng));
     sk[0].nullify();
-    pk.push(sk[0].get_pk(0, &params, n, &lagrange_polys).await);
+    pk.push(sk[0].get_pk(0, &params, n, &lagrange_polys).await.unwrap());
 
     for i in 1..k {
         sk.push(SecretKey::<E>::new(&mut rng));
         
         
         let t = time::Instant::now();
-        pk.push(tokio::join!(sk[i].get_pk(i, &params, n, &lagrange_polys)).0);
+        pk.push(tokio::join!(sk[i].get_pk(i, &params, n, &lagrange_polys)).0.unwrap());
         println!("{:#?}: {} is valid", t.elapsed(), i);
     }
 
-    let agg_key = AggregateKey::<E>::new(pk.clone(), n, &params);
-    let ct = encrypt::<E>(&agg_key, t, &params);
+    let agg_key = AggregateKey::<E>::new(pk.clone(), n, &params).unwrap();
+    let ct = encrypt::<E>(&agg_key, t, &params).unwrap();
 
     let mut partial_decryptions: Vec<G2> = Vec::new();
     let mut selector: Vec<bool> = Vec::new();
@@ -59,7 +59,7 @@ async fn main() {
     }
 
     let ti = time::Instant::now();
-    let _dec_key = agg_dec(&partial_decryptions, &ct.sa1, &ct.sa2, t, n, &selector, &agg_key, &params).await;
+    let _dec_key = agg_dec(&partial_decryptions, &ct.sa1, &ct.sa2, t, n, &selector, &agg_key, &params).await.unwrap();
     println!("{:#?}: elapsed time for decryption", ti.elapsed());
 
     println!("{}", _dec_key == ct.enc_key);
@@ -2,61 +2,102 @@ use std::{collections::HashMap, io::Cursor};
 
 use ark_serialize::CanonicalDeserialize;
 
-use crate::api::types::{Decrypt, DecryptRequest, G1, G2};
+use crate::{api::{registry::CommitteeRef, types::{Decrypt, DecryptRequest}}, curve::CurveId, dem::Dem, encryption::{header, tag_gamma_g2}, envelope::Envelope, error::Error, weighted::WeightedCommittee};
 
-impl DecryptRequest {
-    pub fn deserialize(self) -> Option<Decrypt> { 
-        let cur = Cursor::new(self.sa1);
-        let tmp_sa1 = CanonicalDeserialize::deserialize_compressed(cur);
-        if tmp_sa1.is_err() {
-            log::error!("can't deserialize sa1");
-            return None;
-        }
-        let sa1: [G1; 2] = tmp_sa1.unwrap();
-
-        let q = Cursor::new(self.sa2);
-        let tmp_sa2 = CanonicalDeserialize::deserialize_compressed(q);
-        if tmp_sa2.is_err() {
-            log::error!("can't deserialize sa2");
-            return None;
-        }
-        let sa2: [G2; 6] = tmp_sa2.unwrap();
+use super::weighted_pks;
 
-        // println!("{:?}", proto.pks);
+impl DecryptRequest {
+    pub fn deserialize<E: CurveId>(self) -> Result<Decrypt<E>, Error> { 
         let mut pks = Vec::new();
-        for (idx, pk) in self.pks.iter().enumerate() {
-            if pk.is_empty() {
-                continue;
-            }
-            let cur = Cursor::new(pk);
-            let tmp_pk = CanonicalDeserialize::deserialize_compressed(cur);
-            if tmp_pk.is_err() {
-                log::error!("can't deserialize pk {}", idx);
-                return None;
+        let mut weights = None;
+        if self.weights.is_empty() {
+            for (idx, pk) in self.pks.iter().enumerate() {
+                if pk.is_empty() {
+                    continue;
+                }
+                pks.push(
+                    CanonicalDeserialize::deserialize_compressed(Cursor::new(pk))
+                        .map_err(|_| Error::MalformedPoint(format!("pk {}", idx)))?
+                );
             }
-            pks.push(tmp_pk.unwrap());
+        } else {
+            let (slot_pks, committee) = weighted_pks(&self.pks, &self.weights)?;
+            pks = slot_pks;
+            weights = Some(committee);
         }
 
         let mut parts = HashMap::new();
         for part in self.parts {
-            let cur = Cursor::new(part.1);
-            let tmp_part = CanonicalDeserialize::deserialize_compressed(cur);
-            if tmp_part.is_err() {
-                log::error!("can't deserialize part {}", part.0);
-                return None;
+            let part_dec = CanonicalDeserialize::deserialize_compressed(Cursor::new(part.1))
+                .map_err(|_| Error::MalformedPoint(format!("part {}", part.0)))?;
+            parts.insert(part.0 as usize, part_dec);
+        }
+
+        let committee = CommitteeRef::from_request(self.committee_id, self.committee_hash);
+
+        // the envelope carries everything else about the ciphertext
+        if !self.envelope.is_empty() {
+            let envelope = Envelope::<E>::decode(&self.envelope)?;
+
+            // only a ciphertext for the tag goes without a proof
+            if let Some(tag) = &envelope.tag {
+                if envelope.gamma_g2 != tag_gamma_g2::<E>(tag) {
+                    return Err(Error::TagMismatch);
+                }
             }
-            parts.insert(part.0 as usize, tmp_part.unwrap());
+
+            let ad = envelope.ad();
+            return Ok(
+                Decrypt {
+                    enc: envelope.payload,
+                    pks,
+                    parts,
+                    gamma_g2: envelope.gamma_g2,
+                    sa1: envelope.sa1,
+                    sa2: envelope.sa2,
+                    iv: envelope.nonce,
+                    ad,
+                    n: weights.as_ref().map_or(envelope.n, WeightedCommittee::n),
+                    t: envelope.t,
+                    check_pks: self.check_pks,
+                    dem: envelope.dem,
+                    proof: envelope.proof,
+                    tagged: envelope.tag.is_some(),
+                    weights,
+                    committee
+                }
+            );
         }
 
-        let cur = Cursor::new(self.gamma_g2);
-        let tmp_gamma_g2 = CanonicalDeserialize::deserialize_compressed(cur);
-        if tmp_gamma_g2.is_err() {
-            log::error!("can't deserialize gamma_g2");
-            return None;
+        let sa1: [E::G1; 2] = CanonicalDeserialize::deserialize_compressed(Cursor::new(self.sa1))
+            .map_err(|_| Error::MalformedPoint("sa1".to_string()))?;
+
+        let sa2: [E::G2; 6] = CanonicalDeserialize::deserialize_compressed(Cursor::new(self.sa2))
+            .map_err(|_| Error::MalformedPoint("sa2".to_string()))?;
+
+        // a tag stands for its gamma_g2, like in /partdec
+        let gamma_g2 = if self.tag.is_empty() {
+            CanonicalDeserialize::deserialize_compressed(Cursor::new(self.gamma_g2))
+                .map_err(|_| Error::MalformedPoint("gamma_g2".to_string()))?
+        } else {
+            if !self.proof.is_empty() {
+                return Err(Error::TaggedProof);
+            }
+            tag_gamma_g2::<E>(&self.tag)
+        };
+
+        let dem = Dem::from_u32(self.dem)?;
+
+        let mut proof = None;
+        if !self.proof.is_empty() {
+            proof = Some(
+                CanonicalDeserialize::deserialize_compressed(Cursor::new(self.proof))
+                    .map_err(|_| Error::MalformedPoint("proof".to_string()))?
+            );
         }
-        let gamma_g2 = tmp_gamma_g2.unwrap();
 
-        Option::from(
+        let ad = header::<E>(&gamma_g2, &sa1, &sa2, self.t as usize);
+        Ok(
             Decrypt {
                 enc: self.enc,
                 pks,
@@ -65,9 +106,16 @@ impl DecryptRequest {
                 sa1,
                 sa2,
                 iv: self.iv,
-                n: self.n as usize,
-                t: self.t as usize
+                ad,
+                n: weights.as_ref().map_or(self.n as usize, WeightedCommittee::n),
+                t: self.t as usize,
+                check_pks: self.check_pks,
+                dem,
+                proof,
+                tagged: !self.tag.is_empty(),
+                weights,
+                committee
             }
         )
     }
-}
\ No newline at end of file
+}
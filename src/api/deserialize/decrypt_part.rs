@@ -2,21 +2,78 @@ use std::io::Cursor;
 
 use ark_serialize::CanonicalDeserialize;
 
-use crate::api::types::{PartDec, PartDecRequest};
+use crate::{api::{registry::CommitteeRef, types::{CheckedPartDec, PartDec, PartDecRequest}}, curve::CurveId, encryption::tag_gamma_g2, error::Error};
 
 impl PartDecRequest {
-    pub fn deserialize(self) -> Option<PartDec> {
-        let cur = Cursor::new(self.gamma_g2);
-        let tmp = CanonicalDeserialize::deserialize_compressed(cur);
-        if tmp.is_err() {
-            log::error!("can't read gamma_g2");
-            return None;
+    pub fn deserialize<E: CurveId>(self) -> Result<PartDec<E>, Error> {
+        let mut requester_pk = None;
+        if !self.requester_pk.is_empty() {
+            requester_pk = Some(
+                CanonicalDeserialize::deserialize_compressed(Cursor::new(&self.requester_pk))
+                    .map_err(|_| Error::MalformedPoint("requester_pk".to_string()))?
+            );
         }
-        let gamma_g2 = tmp.unwrap();
-        Option::from(
+
+        // a tag stands for its gamma_g2, and for every ciphertext of the tag at once
+        if !self.tag.is_empty() {
+            if !self.proof.is_empty() {
+                return Err(Error::TaggedProof);
+            }
+            return Ok(
+                PartDec {
+                    gamma_g2: tag_gamma_g2::<E>(&self.tag),
+                    prove: self.prove,
+                    tag: Some(self.tag),
+                    requester_pk,
+                    checked: None
+                }
+            );
+        }
+
+        let gamma_g2 = CanonicalDeserialize::deserialize_compressed(Cursor::new(self.gamma_g2))
+            .map_err(|_| Error::MalformedPoint("gamma_g2".to_string()))?;
+
+        // without a proof the request is a bare gamma_g2
+        if self.proof.is_empty() {
+            return Ok(
+                PartDec {
+                    gamma_g2,
+                    prove: self.prove,
+                    tag: None,
+                    requester_pk,
+                    checked: None
+                }
+            );
+        }
+
+        // the proof is checked against the aggregate key of a registered committee, aggregating keys
+        // sent along with it would let anyone have the server do it for every request
+        let committee = CommitteeRef::from_request(self.committee_id, self.committee_hash)
+            .ok_or(Error::UnregisteredCommittee)?;
+
+        let sa1: [E::G1; 2] = CanonicalDeserialize::deserialize_compressed(Cursor::new(self.sa1))
+            .map_err(|_| Error::MalformedPoint("sa1".to_string()))?;
+
+        let sa2: [E::G2; 6] = CanonicalDeserialize::deserialize_compressed(Cursor::new(self.sa2))
+            .map_err(|_| Error::MalformedPoint("sa2".to_string()))?;
+
+        let proof = CanonicalDeserialize::deserialize_compressed(Cursor::new(self.proof))
+            .map_err(|_| Error::MalformedPoint("proof".to_string()))?;
+
+        Ok(
             PartDec {
-                gamma_g2
+                gamma_g2,
+                prove: self.prove,
+                tag: None,
+                requester_pk,
+                checked: Some(CheckedPartDec {
+                    sa1,
+                    sa2,
+                    t: self.t as usize,
+                    proof,
+                    committee
+                })
             }
         )
     }
-}
\ No newline at end of file
+}
@@ -2,32 +2,53 @@ use std::io::Cursor;
 
 use ark_serialize::CanonicalDeserialize;
 
-use crate::api::types::{Encrypt, EncryptRequest};
+use ark_ec::pairing::Pairing;
+
+use crate::{api::{registry::CommitteeRef, types::{Encrypt, EncryptRequest}}, dem::Dem, error::Error, weighted::WeightedCommittee};
+
+use super::weighted_pks;
 
 impl EncryptRequest {
-    pub fn deserialize(self) -> Option<Encrypt> {
+    pub fn deserialize<E: Pairing>(self) -> Result<Encrypt<E>, Error> {
         let mut pks = Vec::new();
-        // println!("len: {}", proto.pks.len());
-        for (idx, pk) in self.pks.iter().enumerate() {
-            if pk.is_empty() {
-                continue;
+        let mut weights = None;
+        if self.weights.is_empty() {
+            for (idx, pk) in self.pks.iter().enumerate() {
+                if pk.is_empty() {
+                    continue;
+                }
+                pks.push(
+                    CanonicalDeserialize::deserialize_compressed(Cursor::new(pk))
+                        .map_err(|_| Error::MalformedPoint(format!("pk {}", idx)))?
+                );
             }
-            let cur = Cursor::new(pk);
-            let tmp_pk = CanonicalDeserialize::deserialize_compressed(cur);
-            if tmp_pk.is_err() {
-                log::error!("can't deserialize pk {}", idx);
-                return None;
-            }
-            pks.push(tmp_pk.unwrap());
+        } else {
+            let (slot_pks, committee) = weighted_pks(&self.pks, &self.weights)?;
+            pks = slot_pks;
+            weights = Some(committee);
         }
-        
-        Option::from(
+        let n = weights.as_ref().map_or(self.n as usize, WeightedCommittee::n);
+
+        let dem = Dem::for_encryption(self.dem)?;
+
+        if self.cca && !self.tag.is_empty() {
+            return Err(Error::TaggedProof);
+        }
+
+        Ok(
             Encrypt {
                 msg: self.msg,
                 pks,
                 t: self.t as usize,
-                n: self.n as usize
+                n,
+                check_pks: self.check_pks,
+                dem,
+                envelope: self.envelope,
+                cca: self.cca,
+                weights,
+                committee: CommitteeRef::from_request(self.committee_id, self.committee_hash),
+                tag: if self.tag.is_empty() { None } else { Some(self.tag) }
             }
         )
     }
-}
\ No newline at end of file
+}
@@ -2,22 +2,21 @@ use std::io::Cursor;
 
 use ark_serialize::CanonicalDeserialize;
 
-use crate::{api::types::{IsValid, IsValidRequest, E}, setup::PublicKey};
+use ark_ec::pairing::Pairing;
+
+use crate::{api::types::{IsValid, IsValidRequest}, error::Error, setup::PublicKey};
 
 impl IsValidRequest {
-    pub fn deserialize(self) -> Option<IsValid> {
+    pub fn deserialize<E: Pairing>(self) -> Result<IsValid<E>, Error> {
         let mut cur = Cursor::new(self.pk);
-        let pk_res = PublicKey::<E>::deserialize_compressed(&mut cur);
-        if pk_res.is_err() {
-            return None;
-        }
-        let pk = pk_res.unwrap();
+        let pk = PublicKey::<E>::deserialize_compressed(&mut cur)
+            .map_err(|_| Error::MalformedPoint("pk".to_string()))?;
 
-        return Option::from(
+        Ok(
             IsValid {
                 pk,
                 n: self.n as usize
             }
         )
     }
-}
\ No newline at end of file
+}
@@ -3,4 +3,33 @@ pub mod decrypt;
 pub mod encrypt;
 pub mod pk;
 pub mod verify_part;
-pub mod is_valid;
\ No newline at end of file
+pub mod is_valid;
+pub mod register;
+
+use std::io::Cursor;
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalDeserialize;
+
+use crate::{error::Error, setup::PublicKey, weighted::WeightedCommittee};
+
+/// Keys of a weighted committee, where `pks[i]` is the compressed `Vec<PublicKey>` of party `i` as
+/// `/getpk` returns it. The keys come back slot-ordered without the dummy party, like unweighted ones.
+fn weighted_pks<E: Pairing>(pks: &[Vec<u8>], weights: &[u64]) -> Result<(Vec<PublicKey<E>>, WeightedCommittee), Error> {
+    let committee = WeightedCommittee::new(weights.iter().map(|&weight| weight as usize).collect())?;
+    if pks.len() != weights.len() {
+        return Err(Error::SizeMismatch { what: "parties", expected: weights.len(), got: pks.len() });
+    }
+
+    let mut slot_pks = Vec::new();
+    for (idx, pk) in pks.iter().enumerate() {
+        let party_pks: Vec<PublicKey<E>> = CanonicalDeserialize::deserialize_compressed(Cursor::new(pk))
+            .map_err(|_| Error::MalformedPoint(format!("pk {}", idx)))?;
+        if party_pks.len() != committee.weights()[idx] {
+            return Err(Error::SizeMismatch { what: "slot keys", expected: committee.weights()[idx], got: party_pks.len() });
+        }
+        slot_pks.extend(party_pks);
+    }
+
+    Ok((slot_pks, committee))
+}
@@ -1,12 +1,18 @@
-use crate::api::types::{PKRequest, PK};
+use crate::{api::types::{PKRequest, PK}, error::Error, weighted::WeightedCommittee};
 
 impl PKRequest {
-    pub fn deserialize(self) -> Option<PK> {
-        return Option::from(
+    pub fn deserialize(self) -> Result<PK, Error> {
+        let mut weights = None;
+        if !self.weights.is_empty() {
+            weights = Some(WeightedCommittee::new(self.weights.iter().map(|&weight| weight as usize).collect())?);
+        }
+
+        Ok(
             PK {
                 id: self.id as usize,
-                n: self.n as usize
+                n: weights.as_ref().map_or(self.n as usize, WeightedCommittee::n),
+                weights
             }
         )
     }
-}
\ No newline at end of file
+}
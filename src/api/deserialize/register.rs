@@ -0,0 +1,37 @@
+use std::io::Cursor;
+
+use ark_serialize::CanonicalDeserialize;
+
+use ark_ec::pairing::Pairing;
+
+use crate::{api::types::{Register, RegisterRequest}, error::Error, weighted::WeightedCommittee};
+
+use super::weighted_pks;
+
+impl RegisterRequest {
+    pub fn deserialize<E: Pairing>(self) -> Result<Register<E>, Error> {
+        let mut pks = Vec::new();
+        let mut weights = None;
+        if self.weights.is_empty() {
+            for (idx, pk) in self.pks.iter().enumerate() {
+                pks.push(
+                    CanonicalDeserialize::deserialize_compressed(Cursor::new(pk))
+                        .map_err(|_| Error::MalformedPoint(format!("pk {}", idx)))?
+                );
+            }
+        } else {
+            let (slot_pks, committee) = weighted_pks(&self.pks, &self.weights)?;
+            pks = slot_pks;
+            weights = Some(committee);
+        }
+
+        Ok(
+            Register {
+                id: self.id,
+                n: weights.as_ref().map_or(self.n as usize, WeightedCommittee::n),
+                pks,
+                weights
+            }
+        )
+    }
+}
@@ -1,40 +1,67 @@
-use std::io::Cursor;
+use std::{collections::HashMap, io::Cursor};
 
 use ark_serialize::CanonicalDeserialize;
 
-use crate::api::types::{VerifyPart, VerifyPartRequest};
+use ark_ec::pairing::Pairing;
+
+use crate::{api::types::{BatchVerifyPart, BatchVerifyPartRequest, VerifyPart, VerifyPartRequest}, error::Error};
 
 impl VerifyPartRequest {
-    pub fn deserialize(self) -> Option<VerifyPart> {
-        let mut cur = Cursor::new(self.gamma_g2);
-        let mut tmp_g2 = CanonicalDeserialize::deserialize_compressed(cur);
-        if tmp_g2.is_err() {
-            log::error!("can't read gamma_g2");
-            return None;
-        }
-        let gamma_g2 = tmp_g2.unwrap();
-    
-        cur = Cursor::new(self.pk);
-        let tmp_pk = CanonicalDeserialize::deserialize_compressed(cur);
-        if tmp_pk.is_err() {
-            log::error!("can't read pk");
-            return None;
-        }
-        let pk = tmp_pk.unwrap();
-    
-        cur = Cursor::new(self.part_dec);
-        tmp_g2 = CanonicalDeserialize::deserialize_compressed(cur);
-        if tmp_g2.is_err() {
-            log::error!("can't read part_dec");
-            return None;
+    pub fn deserialize<E: Pairing>(self) -> Result<VerifyPart<E>, Error> {
+        let gamma_g2 = CanonicalDeserialize::deserialize_compressed(Cursor::new(self.gamma_g2))
+            .map_err(|_| Error::MalformedPoint("gamma_g2".to_string()))?;
+
+        let pk = CanonicalDeserialize::deserialize_compressed(Cursor::new(self.pk))
+            .map_err(|_| Error::MalformedPoint("pk".to_string()))?;
+
+        let part_dec = CanonicalDeserialize::deserialize_compressed(Cursor::new(self.part_dec))
+            .map_err(|_| Error::MalformedPoint("part_dec".to_string()))?;
+
+        let mut proof = None;
+        if !self.proof.is_empty() {
+            proof = Some(
+                CanonicalDeserialize::deserialize_compressed(Cursor::new(self.proof))
+                    .map_err(|_| Error::MalformedPoint("proof".to_string()))?
+            );
         }
-        let part_dec = tmp_g2.unwrap();
     
-        Option::from(
+        Ok(
             VerifyPart {
                 gamma_g2,
                 pk,
-                part_dec
+                part_dec,
+                proof
+            }
+        )
+    }
+}
+
+
+impl BatchVerifyPartRequest {
+    pub fn deserialize<E: Pairing>(self) -> Result<BatchVerifyPart<E>, Error> {
+        let gamma_g2 = CanonicalDeserialize::deserialize_compressed(Cursor::new(self.gamma_g2))
+            .map_err(|_| Error::MalformedPoint("gamma_g2".to_string()))?;
+
+        let mut pks = Vec::new();
+        for (idx, pk) in self.pks.iter().enumerate() {
+            pks.push(
+                CanonicalDeserialize::deserialize_compressed(Cursor::new(pk))
+                    .map_err(|_| Error::MalformedPoint(format!("pk {}", idx)))?
+            );
+        }
+
+        let mut parts = HashMap::new();
+        for part in self.parts {
+            let part_dec = CanonicalDeserialize::deserialize_compressed(Cursor::new(part.1))
+                .map_err(|_| Error::MalformedPoint(format!("part {}", part.0)))?;
+            parts.insert(part.0 as usize, part_dec);
+        }
+
+        Ok(
+            BatchVerifyPart {
+                gamma_g2,
+                pks,
+                parts
             }
         )
     }
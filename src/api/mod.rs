@@ -1,3 +1,4 @@
 pub mod types;
 pub mod routes;
-pub mod deserialize;
\ No newline at end of file
+pub mod deserialize;
+pub mod registry;
\ No newline at end of file
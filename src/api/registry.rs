@@ -0,0 +1,304 @@
+use std::{collections::HashMap, fs, io, path::PathBuf, sync::{Arc, RwLock}};
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use ark_std::One;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    curve::{read_file, write_file, CurveId}, error::{check_committee, Error}, kzg::UniversalParams, setup::{get_pk_exp, pad_pks, AggregateKey, PublicKey, SecretKey}, utils::{domain_size, LagrangePolyHelper}, weighted::WeightedCommittee
+};
+
+const COMMITTEE_DOMAIN: &[u8] = b"silent-threshold/committee/v1";
+const TMP_EXTENSION: &str = "tmp";
+
+/// How a request refers to a registered committee
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CommitteeRef {
+    Id(String),
+    Hash(Vec<u8>),
+}
+
+impl CommitteeRef {
+    /// `Id` if `id` is set, `Hash` if `hash` is, `None` for a request that carries its own keys
+    pub fn from_request(id: String, hash: Vec<u8>) -> Option<Self> {
+        if !id.is_empty() {
+            return Some(CommitteeRef::Id(id));
+        }
+        if !hash.is_empty() {
+            return Some(CommitteeRef::Hash(hash));
+        }
+        None
+    }
+}
+
+/// A committee along with its aggregate key, built once when it is registered
+pub struct Committee<E: Pairing> {
+    pub id: String,
+    /// number of parties, the dummy one included
+    pub n: usize,
+    pub weights: Option<WeightedCommittee>,
+    /// keys of all slots, the dummy party and the padding included
+    pub agg_key: AggregateKey<E>,
+    /// SHA-256 of `n`, the weights and the keys of the members
+    pub hash: [u8; 32],
+}
+
+impl<E: CurveId> Committee<E> {
+    /// Aggregates the keys of the `n - 1` members in `pks`, slot-ordered without the dummy party
+    pub fn new(
+        id: String,
+        mut pks: Vec<PublicKey<E>>,
+        n: usize,
+        weights: Option<WeightedCommittee>,
+        lagrange_polys: &LagrangePolyHelper<E>,
+        params: &UniversalParams<E>,
+    ) -> Result<Self, Error> {
+        check_committee(n, 0, pks.len())?;
+
+        let dummy = SecretKey { sk: E::ScalarField::one() };
+        pks.insert(0, get_pk_exp(&dummy, 0, domain_size(n), lagrange_polys)?);
+        let agg_key = AggregateKey::new(pad_pks(pks, lagrange_polys)?, n, params)?;
+
+        Self::from_agg_key(id, weights, agg_key)
+    }
+
+    /// The committee of an `AggregateKey` that was already computed, `SizeMismatch` if it has no hints
+    pub fn from_agg_key(id: String, weights: Option<WeightedCommittee>, agg_key: AggregateKey<E>) -> Result<Self, Error> {
+        agg_key.check_hints()?;
+
+        let mut committee = Committee { id, n: agg_key.n, weights, agg_key, hash: [0; 32] };
+        committee.hash = Sha256::new()
+            .chain_update(COMMITTEE_DOMAIN)
+            .chain_update(committee.hashed_bytes())
+            .finalize()
+            .into();
+
+        Ok(committee)
+    }
+
+    /// keys of the members, slot-ordered without the dummy party
+    pub fn members(&self) -> &[PublicKey<E>] {
+        &self.agg_key.pk[1..self.n]
+    }
+
+    fn weights_u64(&self) -> Vec<u64> {
+        self.weights.as_ref()
+            .map(|committee| committee.weights().iter().map(|&weight| weight as u64).collect())
+            .unwrap_or_default()
+    }
+
+    // n, the weights and the members
+    fn hashed_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        (self.n as u64, self.weights_u64(), self.members().to_vec()).serialize_compressed(&mut bytes).unwrap();
+        bytes
+    }
+}
+
+/// Registered committees by id, shared by the workers and written to `dir` as they are registered
+#[derive(Clone)]
+pub struct Registry<E: Pairing> {
+    committees: Arc<RwLock<HashMap<String, Arc<Committee<E>>>>>,
+    dir: Option<PathBuf>,
+    /// most committees the registry takes, /register being open to anyone
+    max: usize,
+}
+
+impl<E: CurveId> Registry<E> {
+    /// An empty registry of at most `max` committees, kept in memory only without `dir`
+    pub fn new(dir: Option<PathBuf>, max: usize) -> Self {
+        Registry { committees: Arc::new(RwLock::new(HashMap::new())), dir, max }
+    }
+
+    /// `RegistryFull` once `max` committees are registered
+    pub fn check_full(&self) -> Result<(), Error> {
+        if self.committees.read().unwrap().len() >= self.max {
+            return Err(Error::RegistryFull(self.max));
+        }
+        Ok(())
+    }
+
+    /// `UnknownCommittee` unless a committee with this id or hash is registered
+    pub fn get(&self, reference: &CommitteeRef) -> Result<Arc<Committee<E>>, Error> {
+        let committees = self.committees.read().unwrap();
+        let committee = match reference {
+            CommitteeRef::Id(id) => committees.get(id),
+            CommitteeRef::Hash(hash) => committees.values().find(|committee| committee.hash[..] == hash[..]),
+        };
+
+        committee.cloned().ok_or_else(|| match reference {
+            CommitteeRef::Id(id) => Error::UnknownCommittee(id.clone()),
+            CommitteeRef::Hash(hash) => Error::UnknownCommittee(hex::encode(hash)),
+        })
+    }
+
+    /// Registers `committee` and writes it to `dir`, registering the same committee again under its id
+    /// is a no-op and `CommitteeExists` is returned if the id is taken by another one. A committee
+    /// that can't be written isn't registered either, `Storage` is returned.
+    pub async fn insert(&self, committee: Committee<E>) -> Result<Arc<Committee<E>>, Error> {
+        if let Some(registered) = self.registered(&self.committees.read().unwrap(), &committee)? {
+            return Ok(registered);
+        }
+
+        // written on a blocking thread without holding the lock, next to its place and renamed so
+        // that a crash never leaves half a committee
+        let path = self.dir.as_ref().map(|dir| dir.join(hex::encode(committee.hash)));
+        if let Some(path) = path.clone() {
+            let stored = write_file::<E, _>(&(committee.id.clone(), committee.weights_u64(), committee.agg_key.clone()));
+            blocking(move || {
+                let tmp = path.with_extension(TMP_EXTENSION);
+                fs::write(&tmp, stored)
+                    .and_then(|_| fs::rename(&tmp, &path))
+                    .map_err(|err| {
+                        let _ = fs::remove_file(&tmp);
+                        Error::Storage(format!("{}: {}", path.display(), err))
+                    })
+            }).await?;
+        }
+
+        let registered = self.register(committee);
+        // another committee took the id or the last place while this one was written
+        if let (Err(_), Some(path)) = (&registered, path) {
+            let _ = blocking(move || Ok(fs::remove_file(path))).await;
+        }
+        registered
+    }
+
+    // the committee registered under the id of `committee` if it is the same one, `CommitteeExists`
+    // if it's another one and `RegistryFull` if there's no room left for it
+    fn registered(
+        &self,
+        committees: &HashMap<String, Arc<Committee<E>>>,
+        committee: &Committee<E>,
+    ) -> Result<Option<Arc<Committee<E>>>, Error> {
+        if let Some(registered) = committees.get(&committee.id) {
+            if registered.hash != committee.hash {
+                return Err(Error::CommitteeExists(committee.id.clone()));
+            }
+            return Ok(Some(registered.clone()));
+        }
+        if committees.len() >= self.max {
+            return Err(Error::RegistryFull(self.max));
+        }
+        Ok(None)
+    }
+
+    fn register(&self, committee: Committee<E>) -> Result<Arc<Committee<E>>, Error> {
+        let mut committees = self.committees.write().unwrap();
+        if let Some(registered) = self.registered(&committees, &committee)? {
+            return Ok(registered);
+        }
+
+        let committee = Arc::new(committee);
+        committees.insert(committee.id.clone(), committee.clone());
+        Ok(committee)
+    }
+
+    /// Registers the committees written to `dir`, creating it if needed, and returns their number.
+    /// Their aggregate keys are read as they were computed at registration, the committees past `max`
+    /// are skipped.
+    pub fn load(&self) -> io::Result<usize> {
+        let dir = match &self.dir {
+            Some(dir) => dir,
+            None => return Ok(0),
+        };
+        fs::create_dir_all(dir)?;
+
+        let mut loaded = 0;
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            // left behind by a write that didn't complete
+            if path.extension().is_some_and(|ext| ext == TMP_EXTENSION) {
+                log::warn!("skipping {}", path.display());
+                continue;
+            }
+            if let Err(err) = self.check_full() {
+                log::warn!("skipping {}: {}", path.display(), err);
+                continue;
+            }
+            let invalid = |err: Error| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), err));
+
+            let (id, weights, agg_key): (String, Vec<u64>, AggregateKey<E>) = read_file::<E, _>(&fs::read(&path)?)
+                .map_err(|err| invalid(Error::from(err)))?;
+            let weights = if weights.is_empty() {
+                None
+            } else {
+                Some(WeightedCommittee::new(weights.iter().map(|&weight| weight as usize).collect()).map_err(invalid)?)
+            };
+
+            let committee = Committee::from_agg_key(id, weights, agg_key).map_err(invalid)?;
+            self.register(committee).map_err(invalid)?;
+            loaded += 1;
+        }
+
+        Ok(loaded)
+    }
+}
+
+// runs `f` on the blocking threads of tokio, out of the workers that serve the requests
+async fn blocking<T, F>(f: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Error> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await.map_err(|err| Error::Storage(err.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_committee, TestCommittee};
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[tokio::test]
+    async fn test_registry() {
+        let mut rng = ark_std::test_rng();
+        let TestCommittee { params: kzg_setup, lagrange_helper, .. } = test_committee::<E>(2, &mut rng).await;
+
+        let pks: Vec<PublicKey<E>> = (0..2)
+            .map(|_| get_pk_exp(&SecretKey::new(&mut rng), 1, 2, &lagrange_helper).unwrap())
+            .collect();
+        let committee = |pk: &PublicKey<E>| Committee::new("a".to_string(), vec![pk.clone()], 2, None, &lagrange_helper, &kzg_setup).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("silent-threshold-registry-{}", std::process::id()));
+        let registry = Registry::<E>::new(Some(dir.clone()), 2);
+        fs::create_dir_all(&dir).unwrap();
+
+        let registered = registry.insert(committee(&pks[0])).await.unwrap();
+        assert_eq!(registry.insert(committee(&pks[0])).await.unwrap().hash, registered.hash);
+        assert_eq!(registry.insert(committee(&pks[1])).await.err(), Some(Error::CommitteeExists("a".to_string())));
+        assert_eq!(registry.get(&CommitteeRef::Hash(registered.hash.to_vec())).unwrap().id, "a");
+        assert_eq!(registry.get(&CommitteeRef::Id("b".to_string())).err(), Some(Error::UnknownCommittee("b".to_string())));
+
+        // a committee that can't be written isn't registered
+        let unwritable = Registry::<E>::new(Some(dir.join("missing")), 2);
+        assert!(matches!(unwritable.insert(committee(&pks[0])).await, Err(Error::Storage(_))));
+        assert_eq!(unwritable.get(&CommitteeRef::Id("a".to_string())).err(), Some(Error::UnknownCommittee("a".to_string())));
+
+        let mut other = committee(&pks[1]);
+        other.id = "b".to_string();
+        registry.insert(other).await.unwrap();
+        let mut third = committee(&pks[1]);
+        third.id = "c".to_string();
+        assert_eq!(registry.check_full(), Err(Error::RegistryFull(2)));
+        assert_eq!(registry.insert(third).await.err(), Some(Error::RegistryFull(2)));
+        assert_eq!(registry.insert(committee(&pks[0])).await.unwrap().hash, registered.hash);
+
+        // leftovers of an interrupted write are skipped
+        fs::write(dir.join("leftover.tmp"), b"").unwrap();
+        let reloaded = Registry::<E>::new(Some(dir.clone()), 2);
+        assert_eq!(reloaded.load().unwrap(), 2);
+        let loaded = reloaded.get(&CommitteeRef::Id("a".to_string())).unwrap();
+        assert_eq!(loaded.hash, registered.hash);
+        assert_eq!(loaded.members()[0].bls_pk, registered.members()[0].bls_pk);
+
+        // the committees past the cap are skipped
+        let capped = Registry::<E>::new(Some(dir.clone()), 1);
+        assert_eq!(capped.load().unwrap(), 1);
+        assert_eq!(capped.check_full(), Err(Error::RegistryFull(1)));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
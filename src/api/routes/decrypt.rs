@@ -1,85 +1,211 @@
 use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
 use actix_web::{HttpRequest, HttpResponse};
 
-use ark_std::{log2, Zero};
+use std::collections::HashMap;
+
+use ark_std::Zero;
 
 use rand::rngs::OsRng;
-use sha2::{Sha256, Digest};
-use block_modes::BlockMode;
 
-use crate::setup::{get_pk_exp, AggregateKey, SecretKey};
-use crate::decryption::agg_dec;
+use crate::error::Error;
+use crate::setup::SecretKey;
+use crate::decryption::{agg_dec, agg_dec_cca, invalid_parts};
 
 use crate::api::types::*;
+use crate::curve::CurveId;
+
+use super::{check_parts, error_response, party_indices, request_committee};
 
-pub async fn decrypt_route(config: HttpRequest, data: ProtoBuf<DecryptRequest>) -> HttpResponse {
-    let datum = config.app_data::<Data>().unwrap();
+pub async fn decrypt_route<E: CurveId>(config: HttpRequest, data: ProtoBuf<DecryptRequest>) -> HttpResponse {
+    let datum = config.app_data::<Data<E>>().unwrap();
     let kzg_setup = datum.clone().kzg_setup;
 
-    let params_res = data.0.deserialize();
-    if params_res.is_none() {
-        log::error!("can't deserialize decrypt params");
-        return HttpResponse::BadRequest().finish();
-    }
-    let params = params_res.unwrap();
+    let params = match data.0.deserialize::<E>() {
+        Ok(params) => params,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
 
-    let mut selector: Vec<bool> = Vec::new();
-    selector.push(true);    
-    
-    let mut rng = OsRng;
+    // the parts of a ciphertext without a proof were only issued if the servers don't require one
+    if datum.require_proof && params.proof.is_none() && !params.tagged {
+        return error_response(HttpResponse::Forbidden(), Error::MissingProof);
+    }
 
-    let mut sk_zero: SecretKey<E> = SecretKey::new(&mut rng);
+    let check = datum.check_pks || params.check_pks;
+    let committee = request_committee(datum, params.committee.as_ref(), params.pks, params.n, params.t, params.weights, check).await;
+    let committee = match committee {
+        Ok(committee) => committee,
+        Err(resp) => return resp,
+    };
+    let n = committee.n;
+
+    // a party of a weighted committee signs all of its slots, the parts are then keyed by slot - 1
+    // like unweighted ones
+    let parts = match &committee.weights {
+        Some(weights) => weights.expand_parts(&params.parts)
+            .map(|parts| parts.into_iter().map(|(slot, part)| (slot - 1, part)).collect()),
+        None => check_parts(&params.parts, n - 1).map(|_| params.parts),
+    };
+    let parts: HashMap<usize, E::G2> = match parts {
+        Ok(parts) => parts,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
+
+    let mut sk_zero: SecretKey<E> = SecretKey::new(&mut OsRng);
     sk_zero.nullify();
 
-    let mut partial_decryptions: Vec<G2> = Vec::new();
+    // drop the shares that don't match their public key, the others may still be enough
+    let invalid = invalid_parts(&params.gamma_g2, &parts, committee.members());
+    let excluded = party_indices(&invalid, committee.weights.as_ref());
+    if !excluded.is_empty() {
+        log::error!("excluding invalid partial decryptions of parties {:?}", excluded);
+    }
+
+    if parts.len() - invalid.len() < params.t {
+        log::error!("{} valid partial decryptions, {} needed", parts.len() - invalid.len(), params.t);
+        let resp = HttpResponse::UnavailableForLegalReasons().protobuf(DecryptResponse { result: Vec::new(), excluded });
+        if resp.is_err() {
+            log::error!("can't cast the result to DecryptResponse");
+            return HttpResponse::InternalServerError().finish();
+        }
+        return resp.unwrap();
+    }
+
+    let mut selector: Vec<bool> = Vec::new();
+    selector.push(true);
+
+    let mut partial_decryptions: Vec<E::G2> = Vec::new();
     partial_decryptions.push(sk_zero.partial_decryption(params.gamma_g2));
 
-    for idx in 0..params.n {
-        if params.parts.contains_key(&idx) {
+    // request index idx is slot idx + 1, slot 0 being the dummy party. agg_dec takes t + 1 selected
+    // parties or more, the dummy one included, and selects the padding itself along with raising t
+    // by its size, so the first t valid shares are enough and the ones past them are left out
+    let mut selected = 0;
+    for idx in 0..n - 1 {
+        if selected < params.t && parts.contains_key(&idx) && !invalid.contains(&idx) {
+            selected += 1;
             selector.push(true);
-            partial_decryptions.push(*params.parts.get(&idx).unwrap());
+            partial_decryptions.push(*parts.get(&idx).unwrap());
         } else {
             selector.push(false);
-            partial_decryptions.push(G2::zero());
+            partial_decryptions.push(E::G2::zero());
         }
     }
 
-    let mut pks = params.pks;
-
-    let l = log2(params.n) as usize - 1;
-    let lagrange_helper = &datum.lagrange_helpers[l];
-
-    pks.insert(0, get_pk_exp(&sk_zero, 0, params.n, &lagrange_helper));
-
-    //println!("{:#?}, {:#?}, {:#?}, {}, {}", partial_decryptions, partial_decryptions.len(), params.parts.len(), params.t, params.n);
-
-    let aggregated = AggregateKey::<E>::new(pks, params.n, &kzg_setup);
-    let key = agg_dec(&partial_decryptions, &params.sa1, &params.sa2, params.t, params.n, &selector, &aggregated, &kzg_setup).await;
+    let aggregated = &committee.agg_key;
+    let key_res = match &params.proof {
+        Some(proof) => agg_dec_cca(&partial_decryptions, &params.gamma_g2, &params.sa1, &params.sa2, proof, params.t, n, &selector, aggregated, &kzg_setup).await,
+        None => agg_dec(&partial_decryptions, &params.sa1, &params.sa2, params.t, n, &selector, aggregated, &kzg_setup).await
+    };
+    let key = match key_res {
+        Ok(key) => key,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
 
-    let mut hasher = Sha256::new();
-    hasher.update(key.to_string().as_bytes());
-    let result = hasher.finalize();
+    let key = params.dem.derive_key(&key);
 
-    let key = result.as_slice();
+    let result = match params.dem.decrypt(&key, &params.iv, &params.enc, &params.ad) {
+        Ok(result) => result,
+        Err(err) => return error_response(HttpResponse::UnavailableForLegalReasons(), err),
+    };
 
-    let cipher_dec_res = Aes256Cbc::new_from_slices(&key, &params.iv);
-    if cipher_dec_res.is_err() {
-        log::error!("key or params.decrypt.iv is wrong");
-        return HttpResponse::BadRequest().finish();
-    }
-    let cipher_dec = cipher_dec_res.unwrap();
-
-    let decrypted_res = cipher_dec.decrypt_vec(&params.enc);
-    if decrypted_res.is_err() {
-        log::error!("failed to decrypt the data, {}", decrypted_res.err().unwrap());
-        return HttpResponse::UnavailableForLegalReasons().finish();
-    }
-    let result = decrypted_res.unwrap();
-
-    let resp = HttpResponse::Ok().protobuf(Response { result });
+    let resp = HttpResponse::Ok().protobuf(DecryptResponse { result, excluded });
     if resp.is_err() {
-        log::error!("can't cast the result to ResultProto");
+        log::error!("can't cast the result to DecryptResponse");
         return HttpResponse::InternalServerError().finish();
     }
     resp.unwrap()
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{test, web, App};
+    use prost::Message;
+
+    use crate::api::registry::{Committee, Registry};
+    use crate::api::routes::{decrypt_part_route, encrypt_route};
+    use crate::dem::Dem;
+    use crate::encryption::encrypt;
+    use crate::envelope::Envelope;
+    use crate::utils::{test_committee, TestCommittee};
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[actix_web::test]
+    async fn test_decrypt_tag() {
+        let mut rng = ark_std::test_rng();
+        let n = 2;
+        let TestCommittee { params: kzg_setup, lagrange_helper, sk, pk, agg_key } = test_committee::<E>(n, &mut rng).await;
+
+        let committees = Registry::new(None, 1);
+        committees.insert(Committee::new("a".to_string(), pk[1..].to_vec(), n, None, &lagrange_helper, &kzg_setup).unwrap()).await.unwrap();
+
+        let data = Data {
+            kzg_setup: kzg_setup.clone(),
+            sk: sk[1].clone(),
+            lagrange_helpers: HashMap::from([(n, lagrange_helper)]),
+            is_valid_helpers: HashMap::new(),
+            check_pks: false,
+            require_proof: true,
+            require_requester: false,
+            committees,
+        };
+        let app = test::init_service(App::new()
+            .app_data(data)
+            .service(web::resource("/encrypt").route(web::post().to(encrypt_route::<E>)))
+            .service(web::resource("/partdec").route(web::post().to(decrypt_part_route::<E>)))
+            .service(web::resource("/decrypt").route(web::post().to(decrypt_route::<E>)))).await;
+
+        let post = |uri: &str, body: Vec<u8>| test::TestRequest::post()
+            .uri(uri)
+            .insert_header(("content-type", "application/protobuf"))
+            .set_payload(body)
+            .to_request();
+
+        let tag = b"block 42".to_vec();
+        let req_encrypt = EncryptRequest { msg: b"Hello, world!".to_vec(), t: 1, committee_id: "a".to_string(), dem: 1, envelope: true, tag: tag.clone(), ..Default::default() };
+        let resp = test::call_service(&app, post("/encrypt", req_encrypt.encode_to_vec())).await;
+        assert_eq!(resp.status(), 200);
+        let enc = EncryptResponse::decode(test::read_body(resp).await).unwrap();
+
+        let resp = test::call_service(&app, post("/partdec", PartDecRequest { tag: tag.clone(), ..Default::default() }.encode_to_vec())).await;
+        assert_eq!(resp.status(), 200);
+        let part = PartDecResponse::decode(test::read_body(resp).await).unwrap();
+        let parts = HashMap::from([(0, part.result)]);
+
+        // as an envelope, or with the tag in place of gamma_g2 for a ciphertext returned without one
+        let req = DecryptRequest { envelope: enc.envelope, parts: parts.clone(), committee_id: "a".to_string(), ..Default::default() };
+        let resp = test::call_service(&app, post("/decrypt", req.encode_to_vec())).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(DecryptResponse::decode(test::read_body(resp).await).unwrap().result, b"Hello, world!");
+
+        let req = EncryptRequest { envelope: false, ..req_encrypt };
+        let resp = test::call_service(&app, post("/encrypt", req.encode_to_vec())).await;
+        assert_eq!(resp.status(), 200);
+        let enc = EncryptResponse::decode(test::read_body(resp).await).unwrap();
+        let req = DecryptRequest {
+            enc: enc.enc,
+            sa1: enc.sa1,
+            sa2: enc.sa2,
+            iv: enc.iv,
+            t: 1,
+            dem: enc.dem,
+            tag: tag.clone(),
+            parts: parts.clone(),
+            committee_id: "a".to_string(),
+            ..Default::default()
+        };
+        let resp = test::call_service(&app, post("/decrypt", req.encode_to_vec())).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(DecryptResponse::decode(test::read_body(resp).await).unwrap().result, b"Hello, world!");
+
+        // a ciphertext that isn't for the tag doesn't go without a proof by claiming one
+        let ct = encrypt::<E>(&agg_key, 1, &kzg_setup).unwrap();
+        let forged = Envelope::new(&ct, n, Dem::Aes256Gcm, vec![0; 12], vec![0; 16]).with_tag(tag);
+        let req = DecryptRequest { envelope: forged.encode(), parts, committee_id: "a".to_string(), ..Default::default() };
+        let resp = test::call_service(&app, post("/decrypt", req.encode_to_vec())).await;
+        assert_eq!(resp.status(), 400);
+        let err = ErrorResponse::decode(test::read_body(resp).await).unwrap();
+        assert_eq!(err.code, Error::TagMismatch.code());
+    }
+}
@@ -2,32 +2,164 @@ use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
 use actix_web::{HttpRequest, HttpResponse};
 use ark_serialize::CanonicalSerialize;
 
+use crate::error::Error;
+
 use crate::api::types::*;
+use crate::curve::CurveId;
+
+use super::error_response;
 
-pub async fn decrypt_part_route(config: HttpRequest, data: ProtoBuf<PartDecRequest>) -> HttpResponse {
-    let datum = config.app_data::<Data>().unwrap();
+pub async fn decrypt_part_route<E: CurveId>(config: HttpRequest, data: ProtoBuf<PartDecRequest>) -> HttpResponse {
+    let datum = config.app_data::<Data<E>>().unwrap();
     let sk = datum.sk.clone();
 
-    let decrypt_part_res = data.0.deserialize();
-    if decrypt_part_res.is_none() {
-        log::error!("can't deserialize gamma_g2");
-        return HttpResponse::BadRequest().finish();
+    let params = match data.0.deserialize::<E>() {
+        Ok(params) => params,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
+
+    if datum.require_requester && params.requester_pk.is_none() {
+        return error_response(HttpResponse::Forbidden(), Error::MissingRequester);
     }
-    let gamma_g2: G2 = decrypt_part_res.unwrap().gamma_g2;
-    
-    let val = gamma_g2 * sk.sk;
-    
+
+    match &params.checked {
+        Some(checked) => {
+            match check_ciphertext(datum, params.gamma_g2, checked) {
+                Ok(()) => (),
+                Err(err @ Error::UnknownCommittee(_)) => return error_response(HttpResponse::NotFound(), err),
+                Err(err) => return error_response(HttpResponse::BadRequest(), err),
+            }
+        }
+        None => {
+            if datum.require_proof && params.tag.is_none() {
+                return error_response(HttpResponse::Forbidden(), Error::MissingProof);
+            }
+        }
+    }
+
     let mut result = Vec::new();
-    let res = val.serialize_compressed(&mut result);
-    if res.is_err() {
-        log::error!("can't serialize gamma_g2 * sk");
-        return HttpResponse::BadRequest().finish();
+    let mut proof_bytes = Vec::new();
+    if let Some(requester_pk) = params.requester_pk {
+        // only the requester can unblind it, the proof is always sent so that relays can check it
+        let (blinded, proof) = sk.blinded_partial_decryption(params.gamma_g2, requester_pk);
+        if blinded.serialize_compressed(&mut result).is_err() || proof.serialize_compressed(&mut proof_bytes).is_err() {
+            log::error!("can't serialize the blinded partial decryption");
+            return HttpResponse::InternalServerError().finish();
+        }
+    } else {
+        let (val, proof) = if params.prove {
+            let (val, proof) = sk.partial_decryption_with_proof(params.gamma_g2);
+            (val, Some(proof))
+        } else {
+            (sk.partial_decryption(params.gamma_g2), None)
+        };
+
+        let res = val.serialize_compressed(&mut result);
+        if res.is_err() {
+            log::error!("can't serialize gamma_g2 * sk");
+            return HttpResponse::InternalServerError().finish();
+        }
+
+        if let Some(proof) = proof {
+            if proof.serialize_compressed(&mut proof_bytes).is_err() {
+                log::error!("can't serialize the partial decryption proof");
+                return HttpResponse::InternalServerError().finish();
+            }
+        }
     }
 
-    let resp = HttpResponse::Ok().protobuf(Response { result });
+    let resp = HttpResponse::Ok().protobuf(PartDecResponse { result, proof: proof_bytes });
     if resp.is_err() {
         log::error!("can't cast the result to ResultProto");
         return HttpResponse::InternalServerError().finish();
     }
     resp.unwrap()
-}
\ No newline at end of file
+}
+
+/// Takes the registered committee `checked` refers to and checks the `CiphertextProof` against its
+/// aggregate key with `checked_partial_decryption`, so that nothing is signed for a header that isn't
+/// well formed
+fn check_ciphertext<E: CurveId>(datum: &Data<E>, gamma_g2: E::G2, checked: &CheckedPartDec<E>) -> Result<(), Error> {
+    let committee = datum.committees.get(&checked.committee)?;
+
+    datum.sk.checked_partial_decryption(gamma_g2, &checked.sa1, &checked.sa2, checked.t, &checked.proof, &committee.agg_key, &datum.kzg_setup)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use actix_web::{test, web, App};
+    use ark_ec::pairing::Pairing;
+    use ark_serialize::CanonicalDeserialize;
+    use prost::Message;
+
+    use crate::api::registry::Registry;
+    use crate::signature::{self, message_point, verify_share, VerificationKey};
+    use crate::utils::{test_committee, TestCommittee};
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[actix_web::test]
+    async fn test_partdec_is_not_a_signature() {
+        let mut rng = ark_std::test_rng();
+        let n = 2;
+        let TestCommittee { params: kzg_setup, lagrange_helper, sk, pk, agg_key } = test_committee::<E>(n, &mut rng).await;
+        let vk = VerificationKey::new(&agg_key.without_hints(), 1, &kzg_setup).unwrap();
+
+        let data = Data {
+            kzg_setup: kzg_setup.clone(),
+            sk: sk[1].clone(),
+            lagrange_helpers: HashMap::from([(n, lagrange_helper)]),
+            is_valid_helpers: HashMap::new(),
+            check_pks: false,
+            require_proof: true,
+            require_requester: false,
+            committees: Registry::new(None, 1),
+        };
+        let app = test::init_service(App::new()
+            .app_data(data)
+            .service(web::resource("/partdec").route(web::post().to(decrypt_part_route::<E>)))).await;
+
+        let partdec = |req: PartDecRequest| test::TestRequest::post()
+            .uri("/partdec")
+            .insert_header(("content-type", "application/protobuf"))
+            .set_payload(req.encode_to_vec())
+            .to_request();
+
+        let msg = b"block 42 is final";
+        let mut gamma_g2 = Vec::new();
+        message_point::<E>(msg).serialize_compressed(&mut gamma_g2).unwrap();
+
+        // the message point sent as a bare gamma_g2 is refused
+        let resp = test::call_service(&app, partdec(PartDecRequest { gamma_g2: gamma_g2.clone(), ..Default::default() })).await;
+        assert_eq!(resp.status(), 403);
+        let err = ErrorResponse::decode(test::read_body(resp).await).unwrap();
+        assert_eq!(err.code, Error::MissingProof.code());
+
+        // a proof is only checked against a registered committee, not against keys sent along
+        let mut pk_bytes = Vec::new();
+        pk[1].serialize_compressed(&mut pk_bytes).unwrap();
+        let req = PartDecRequest { gamma_g2: gamma_g2.clone(), n: 2, pks: vec![pk_bytes], proof: vec![1], ..Default::default() };
+        let resp = test::call_service(&app, partdec(req)).await;
+        assert_eq!(resp.status(), 400);
+        let err = ErrorResponse::decode(test::read_body(resp).await).unwrap();
+        assert_eq!(err.code, Error::UnregisteredCommittee.code());
+
+        // the message sent as a tag is served without a proof, but hashed under another DST
+        let resp = test::call_service(&app, partdec(PartDecRequest { tag: msg.to_vec(), ..Default::default() })).await;
+        assert_eq!(resp.status(), 200);
+        let part = PartDecResponse::decode(test::read_body(resp).await).unwrap();
+        let part = <E as Pairing>::G2::deserialize_compressed(&part.result[..]).unwrap();
+        assert!(!verify_share::<E>(&pk[1].bls_pk, msg, &part));
+
+        let signature = signature::aggregate(msg, &HashMap::from([(1, part)]), 1, &agg_key, &kzg_setup).await.unwrap();
+        assert!(!signature::verify(&vk, msg, &signature));
+
+        let signature = signature::aggregate(msg, &HashMap::from([(1, sk[1].sign(msg))]), 1, &agg_key, &kzg_setup).await.unwrap();
+        assert!(signature::verify(&vk, msg, &signature));
+    }
+}
@@ -1,65 +1,173 @@
 use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
 use actix_web::{HttpRequest, HttpResponse};
 
-use ark_std::log2;
-use rand::Rng;
 use rand::rngs::OsRng;
-use sha2::{Sha256, Digest};
-use block_modes::BlockMode;
 
-use crate::encryption::encrypt;
-use crate::setup::{get_pk_exp, AggregateKey, SecretKey};
+use crate::encryption::{encrypt, encrypt_cca, encrypt_for_tag};
+use crate::envelope::Envelope;
+use crate::error::Error;
 
 use crate::api::types::*;
+use crate::curve::CurveId;
 
-pub async fn encrypt_route(config: HttpRequest, data: ProtoBuf<EncryptRequest>) -> HttpResponse {
-    let datum = config.app_data::<Data>().unwrap();
+use super::{error_response, request_committee};
+
+pub async fn encrypt_route<E: CurveId>(config: HttpRequest, data: ProtoBuf<EncryptRequest>) -> HttpResponse {
+    let datum = config.app_data::<Data<E>>().unwrap();
     let kzg_setup = datum.kzg_setup.clone();
 
-    let encrypt_data_res = data.0.deserialize();
-    if encrypt_data_res.is_none() {
-        log::error!("can't deserialize encrypt_data");
-        return HttpResponse::BadRequest().finish();
+    let encrypt_data = match data.0.deserialize::<E>() {
+        Ok(encrypt_data) => encrypt_data,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
+
+    // the servers only issue partial decryptions of a ciphertext along with its proof if they require
+    // one, and only check a proof against a registered committee
+    let cca = encrypt_data.cca || (datum.require_proof && encrypt_data.tag.is_none());
+    if cca && encrypt_data.committee.is_none() {
+        return error_response(HttpResponse::BadRequest(), Error::UnregisteredCommittee);
     }
-    let encrypt_data = encrypt_data_res.unwrap();
 
-    let mut pks = encrypt_data.pks;
+    let check = datum.check_pks || encrypt_data.check_pks;
+    let committee = request_committee(datum, encrypt_data.committee.as_ref(), encrypt_data.pks, encrypt_data.n, encrypt_data.t, encrypt_data.weights, check).await;
+    let committee = match committee {
+        Ok(committee) => committee,
+        Err(resp) => return resp,
+    };
+    let aggregated = &committee.agg_key;
 
-    let mut rng = OsRng;
-    
-    let l = log2(encrypt_data.n) as usize - 1;
-    let lagrange_helper = &datum.lagrange_helpers[l];
+    let ct_res = if let Some(tag) = &encrypt_data.tag {
+        encrypt_for_tag(aggregated, encrypt_data.t, &kzg_setup, tag).map(|ct| (ct, None))
+    } else if cca {
+        encrypt_cca(aggregated, encrypt_data.t, &kzg_setup).map(|(ct, proof)| (ct, Some(proof)))
+    } else {
+        encrypt(aggregated, encrypt_data.t, &kzg_setup).map(|ct| (ct, None))
+    };
+    let (ct, proof) = match ct_res {
+        Ok(ct) => ct,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
 
-    let mut sk_zero: SecretKey<E> = SecretKey::new(&mut rng);
-    sk_zero.nullify();
-    pks.insert(0, get_pk_exp(&sk_zero, 0, encrypt_data.n, &lagrange_helper));
+    // an envelope binds all it holds to the payload, which is then only returned inside it
+    let (iv, enc, envelope) = if encrypt_data.envelope {
+        let mut env = Envelope::new(&ct, committee.n, encrypt_data.dem, Vec::new(), Vec::new());
+        if let Some(proof) = &proof {
+            env = env.with_proof(proof.clone());
+        }
+        if let Some(tag) = &encrypt_data.tag {
+            env = env.with_tag(tag.clone());
+        }
+        match env.with_message(&ct.enc_key, &encrypt_data.msg) {
+            Ok(env) => (Vec::new(), Vec::new(), env.encode()),
+            Err(err) => return error_response(HttpResponse::InternalServerError(), err),
+        }
+    } else {
+        let key = encrypt_data.dem.derive_key(&ct.enc_key);
+        match encrypt_data.dem.encrypt(&key, &encrypt_data.msg, &ct.header(), &mut OsRng) {
+            Ok((iv, enc)) => (iv, enc, Vec::new()),
+            Err(err) => return error_response(HttpResponse::InternalServerError(), err),
+        }
+    };
 
-    let aggregated = AggregateKey::<E>::new(pks, encrypt_data.n, &kzg_setup);
-    let ct = encrypt(&aggregated, encrypt_data.t, &kzg_setup);
+    let resp = HttpResponse::Ok().protobuf(EncryptResponse::new(enc, ct, proof, iv, encrypt_data.dem, envelope));
+    if resp.is_err() {
+        log::error!("can't cast the result to ResultProto");
+        return HttpResponse::InternalServerError().finish();
+    }
+    resp.unwrap()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
 
-    let mut hasher = Sha256::new();
-    hasher.update(ct.enc_key.to_string().as_bytes());
-    let result = hasher.clone().finalize();
+    use actix_web::{test, web, App};
+    use ark_serialize::CanonicalSerialize;
+    use prost::Message;
 
-    let key = result.as_slice();
+    use crate::api::registry::{Committee, Registry};
+    use crate::api::routes::{decrypt_part_route, decrypt_route};
+    use crate::utils::{test_committee, TestCommittee};
 
-    let iv = &mut [0u8; 16];
-    rng.fill(iv);
+    type E = ark_bls12_381::Bls12_381;
 
-    let cipher_enc_res = Aes256Cbc::new_from_slices(key, iv);
-    if cipher_enc_res.is_err() {
-        log::error!("can't create Aes256Cbc::new_from_slices(key, iv)");
-        HttpResponse::BadRequest().finish();
-    }
-    let cipher_enc = cipher_enc_res.unwrap();
-    
-    let enc = cipher_enc.encrypt_vec(&encrypt_data.msg);
-    hasher.update(ct.enc_key.to_string().as_bytes());
+    #[actix_web::test]
+    async fn test_default_config_round_trip() {
+        let mut rng = ark_std::test_rng();
+        let n = 2;
+        let TestCommittee { params: kzg_setup, lagrange_helper, sk, pk, .. } = test_committee::<E>(n, &mut rng).await;
 
-    let resp = HttpResponse::Ok().protobuf(EncryptResponse::new(enc, ct, iv.to_vec()));
-    if resp.is_err() {
-        log::error!("can't cast the result to ResultProto");
-        return HttpResponse::InternalServerError().finish();
+        let committees = Registry::new(None, 1);
+        committees.insert(Committee::new("a".to_string(), pk[1..].to_vec(), n, None, &lagrange_helper, &kzg_setup).unwrap()).await.unwrap();
+
+        // what the server runs with unless told otherwise
+        let data = Data {
+            kzg_setup,
+            sk: sk[1].clone(),
+            lagrange_helpers: HashMap::from([(n, lagrange_helper)]),
+            is_valid_helpers: HashMap::new(),
+            check_pks: false,
+            require_proof: true,
+            require_requester: false,
+            committees,
+        };
+        let lax = test::init_service(App::new()
+            .app_data(Data { require_proof: false, ..data.clone() })
+            .service(web::resource("/encrypt").route(web::post().to(encrypt_route::<E>)))).await;
+        let app = test::init_service(App::new()
+            .app_data(data)
+            .service(web::resource("/encrypt").route(web::post().to(encrypt_route::<E>)))
+            .service(web::resource("/partdec").route(web::post().to(decrypt_part_route::<E>)))
+            .service(web::resource("/decrypt").route(web::post().to(decrypt_route::<E>)))).await;
+
+        let post = |uri: &str, body: Vec<u8>| test::TestRequest::post()
+            .uri(uri)
+            .insert_header(("content-type", "application/protobuf"))
+            .set_payload(body)
+            .to_request();
+
+        // keys sent along can't be checked against a proof later on
+        let mut pk_bytes = Vec::new();
+        pk[1].serialize_compressed(&mut pk_bytes).unwrap();
+        let req = EncryptRequest { msg: b"Hello, world!".to_vec(), t: 1, n: 2, pks: vec![pk_bytes.clone()], ..Default::default() };
+        let resp = test::call_service(&app, post("/encrypt", req.encode_to_vec())).await;
+        assert_eq!(resp.status(), 400);
+        let err = ErrorResponse::decode(test::read_body(resp).await).unwrap();
+        assert_eq!(err.code, Error::UnregisteredCommittee.code());
+
+        // nor when the servers don't require proofs, but a ciphertext without one is fine then
+        let req = EncryptRequest { msg: b"Hello, world!".to_vec(), t: 1, n: 2, pks: vec![pk_bytes], cca: true, ..Default::default() };
+        let resp = test::call_service(&lax, post("/encrypt", req.encode_to_vec())).await;
+        assert_eq!(resp.status(), 400);
+        let req = EncryptRequest { cca: false, ..req };
+        let resp = test::call_service(&lax, post("/encrypt", req.encode_to_vec())).await;
+        assert_eq!(resp.status(), 200);
+
+        // without cca the ciphertext still gets a proof
+        let req = EncryptRequest { msg: b"Hello, world!".to_vec(), t: 1, committee_id: "a".to_string(), envelope: true, ..Default::default() };
+        let resp = test::call_service(&app, post("/encrypt", req.encode_to_vec())).await;
+        assert_eq!(resp.status(), 200);
+        let enc = EncryptResponse::decode(test::read_body(resp).await).unwrap();
+        assert!(!enc.proof.is_empty());
+        assert!(enc.enc.is_empty());
+
+        let req = PartDecRequest {
+            gamma_g2: enc.gamma_g2.clone(),
+            sa1: enc.sa1.clone(),
+            sa2: enc.sa2.clone(),
+            t: 1,
+            proof: enc.proof.clone(),
+            committee_id: "a".to_string(),
+            ..Default::default()
+        };
+        let resp = test::call_service(&app, post("/partdec", req.encode_to_vec())).await;
+        assert_eq!(resp.status(), 200);
+        let part = PartDecResponse::decode(test::read_body(resp).await).unwrap();
+
+        let req = DecryptRequest { envelope: enc.envelope, parts: HashMap::from([(0, part.result)]), committee_id: "a".to_string(), ..Default::default() };
+        let resp = test::call_service(&app, post("/decrypt", req.encode_to_vec())).await;
+        assert_eq!(resp.status(), 200);
+        let dec = DecryptResponse::decode(test::read_body(resp).await).unwrap();
+        assert_eq!(dec.result, b"Hello, world!");
     }
-    resp.unwrap()
-}
\ No newline at end of file
+}
@@ -0,0 +1,39 @@
+use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
+use actix_web::{HttpRequest, HttpResponse};
+
+use ark_serialize::*;
+
+use crate::api::types::*;
+use crate::curve::CurveId;
+
+use super::{error_response, registered_committee};
+
+/// The aggregate key of a registered committee, without hints unless `hints` is set, so that
+/// encryptors fetch it once and encrypt without the server
+pub async fn get_agg_key_route<E: CurveId>(config: HttpRequest, data: ProtoBuf<AggKeyRequest>) -> HttpResponse {
+    let datum = config.app_data::<Data<E>>().unwrap();
+    let params = data.0;
+
+    let committee = match registered_committee(datum, params.committee_id, params.committee_hash) {
+        Ok(committee) => committee,
+        Err(err) => return error_response(HttpResponse::NotFound(), err),
+    };
+
+    let mut result = Vec::new();
+    let res = if params.hints {
+        committee.agg_key.serialize_compressed(&mut result)
+    } else {
+        committee.agg_key.without_hints().serialize_compressed(&mut result)
+    };
+    if res.is_err() {
+        log::error!("can't serialize the aggregate key");
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let resp = HttpResponse::Ok().protobuf(Response { result });
+    if resp.is_err() {
+        log::error!("can't cast the result to ResultProto");
+        return HttpResponse::InternalServerError().finish();
+    }
+    resp.unwrap()
+}
@@ -0,0 +1,40 @@
+use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
+use actix_web::{HttpRequest, HttpResponse};
+
+use ark_serialize::*;
+
+use crate::encryption::EncryptionKey;
+
+use crate::api::types::*;
+use crate::curve::CurveId;
+
+use super::{error_response, registered_committee};
+
+/// The `EncryptionKey` of a registered committee for threshold `t`
+pub async fn get_enc_key_route<E: CurveId>(config: HttpRequest, data: ProtoBuf<EncKeyRequest>) -> HttpResponse {
+    let datum = config.app_data::<Data<E>>().unwrap();
+    let params = data.0;
+
+    let committee = match registered_committee(datum, params.committee_id, params.committee_hash) {
+        Ok(committee) => committee,
+        Err(err) => return error_response(HttpResponse::NotFound(), err),
+    };
+
+    let ek = match EncryptionKey::new(&committee.agg_key, params.t as usize, &datum.kzg_setup) {
+        Ok(ek) => ek,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
+
+    let mut result = Vec::new();
+    if ek.serialize_compressed(&mut result).is_err() {
+        log::error!("can't serialize the encryption key");
+        return HttpResponse::InternalServerError().finish();
+    }
+
+    let resp = HttpResponse::Ok().protobuf(Response { result });
+    if resp.is_err() {
+        log::error!("can't cast the result to ResultProto");
+        return HttpResponse::InternalServerError().finish();
+    }
+    resp.unwrap()
+}
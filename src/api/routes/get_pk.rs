@@ -2,31 +2,46 @@ use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
 use actix_web::{HttpRequest, HttpResponse};
 
 use ark_serialize::*;
-use ark_std::log2;
 
 use crate::api::types::*;
+use crate::curve::CurveId;
+use crate::error::Error;
 use crate::setup::get_pk_exp;
+use crate::utils::domain_size;
 
+use super::error_response;
 
-pub async fn get_pk_route(config: HttpRequest, data: ProtoBuf<PKRequest>) -> HttpResponse {
-    let datum = config.app_data::<Data>().unwrap();
+
+pub async fn get_pk_route<E: CurveId>(config: HttpRequest, data: ProtoBuf<PKRequest>) -> HttpResponse {
+    let datum = config.app_data::<Data<E>>().unwrap();
     let sk = &datum.sk;
     // let params = &datum.kzg_setup;
 
-    let pk_res = data.0.deserialize();
-    if pk_res.is_none() {
-        log::error!("can't deserialize pk request");
-        return HttpResponse::InternalServerError().finish();
-    }
-    let pk = pk_res.unwrap();
-
-    let l = log2(pk.n) as usize - 1;
-    let lagrange_helper = &datum.lagrange_helpers[l];
+    let pk = match data.0.deserialize() {
+        Ok(pk) => pk,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
 
-    let pk = get_pk_exp(sk, pk.id + 1, pk.n, &lagrange_helper);
-    
+    // slot 0 is the dummy party, the n - 1 members take the others. A party of a weighted committee
+    // gets the keys of all of its slots as one serialized Vec
     let mut result = Vec::new();
-    let res = pk.serialize_compressed(&mut result);
+    let res = match &pk.weights {
+        Some(committee) => datum.lagrange_helper(pk.n)
+            .and_then(|lagrange_helper| committee.get_pks(sk, pk.id, lagrange_helper))
+            .map(|pks| pks.serialize_compressed(&mut result)),
+        None => datum.lagrange_helper(pk.n)
+            .and_then(|lagrange_helper| {
+                if pk.id >= pk.n - 1 {
+                    return Err(Error::PartyIndex { id: pk.id, n: pk.n - 1 });
+                }
+                get_pk_exp(sk, pk.id + 1, domain_size(pk.n), lagrange_helper)
+            })
+            .map(|pk| pk.serialize_compressed(&mut result)),
+    };
+    let res = match res {
+        Ok(res) => res,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
     if res.is_err() {
         log::error!("can't serialize public key");
         return HttpResponse::InternalServerError().finish();
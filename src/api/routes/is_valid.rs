@@ -0,0 +1,64 @@
+use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
+use actix_web::{HttpRequest, HttpResponse};
+
+use crate::decryption::{invalid_pks, is_valid};
+use crate::error::Error;
+use crate::setup::PublicKey;
+use crate::utils::domain_size;
+use crate::weighted::WeightedCommittee;
+
+use crate::api::types::*;
+use crate::curve::CurveId;
+
+use super::{error_response, party_indices};
+
+pub async fn is_valid_route<E: CurveId>(config: HttpRequest, data: ProtoBuf<IsValidRequest>) -> HttpResponse {
+    let datum = config.app_data::<Data<E>>().unwrap();
+    let kzg_setup = &datum.kzg_setup;
+
+    let params = match data.0.deserialize::<E>() {
+        Ok(params) => params,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
+
+    let helper = match datum.is_valid_helper(params.n) {
+        Ok(helper) => helper,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
+
+    if params.pk.id >= params.n {
+        return error_response(HttpResponse::BadRequest(), Error::PartyIndex { id: params.pk.id, n: params.n });
+    }
+
+    if is_valid(&params.pk, domain_size(params.n), kzg_setup, helper).await {
+        return HttpResponse::Ok().finish();
+    }
+
+    HttpResponse::UnavailableForLegalReasons().finish()
+}
+
+/// Runs `invalid_pks` over the slot-ordered `pks` (dummy party included) and builds
+/// the 422 response listing the offending indices of the request's `pks`, if any. For a weighted
+/// committee these are the indices of the parties.
+pub(super) async fn check_pks<E: CurveId>(datum: &Data<E>, pks: &[PublicKey<E>], n: usize, weights: Option<&WeightedCommittee>) -> Option<HttpResponse> {
+    let helper = match datum.is_valid_helper(n) {
+        Ok(helper) => helper,
+        Err(err) => return Some(error_response(HttpResponse::BadRequest(), err)),
+    };
+
+    let invalid = invalid_pks(pks, domain_size(n), &datum.kzg_setup, helper).await;
+    if invalid.is_empty() {
+        return None;
+    }
+    log::error!("invalid public keys for slots {:?}", invalid);
+
+    // slot 0 is the dummy party, request indices start from slot 1
+    let slots: Vec<usize> = invalid.iter().map(|&slot| slot - 1).collect();
+    let indices = party_indices(&slots, weights);
+    let resp = HttpResponse::UnprocessableEntity().protobuf(IndicesResponse { indices });
+    if resp.is_err() {
+        log::error!("can't cast the result to IndicesResponse");
+        return Some(HttpResponse::InternalServerError().finish());
+    }
+    Some(resp.unwrap())
+}
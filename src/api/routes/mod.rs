@@ -1,11 +1,132 @@
 mod decrypt_part;
 mod decrypt;
 mod encrypt;
+mod get_agg_key;
+mod get_enc_key;
 mod get_pk;
+mod is_valid;
+mod register;
 mod verify_part;
 
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use actix_protobuf::ProtoBufResponseBuilder;
+use actix_web::{HttpResponse, HttpResponseBuilder};
+use rand::rngs::OsRng;
+
+use crate::api::registry::{Committee, CommitteeRef};
+use crate::api::types::{Data, ErrorResponse};
+use crate::curve::CurveId;
+use crate::error::{check_committee, Error};
+use crate::setup::{get_pk_exp, PublicKey, SecretKey};
+use crate::utils::domain_size;
+use crate::weighted::WeightedCommittee;
+
 pub use self::decrypt_part::decrypt_part_route;
 pub use self::decrypt::decrypt_route;
 pub use self::encrypt::encrypt_route;
+pub use self::get_agg_key::get_agg_key_route;
+pub use self::get_enc_key::get_enc_key_route;
 pub use self::get_pk::get_pk_route;
-pub use self::verify_part::verify_part_route;
\ No newline at end of file
+pub use self::is_valid::is_valid_route;
+pub use self::register::register_route;
+pub use self::verify_part::{batch_verify_part_route, verify_part_route};
+
+use self::is_valid::check_pks;
+
+/// Logs `err` and sends it as an `ErrorResponse` with the status of `builder`
+fn error_response(mut builder: HttpResponseBuilder, err: Error) -> HttpResponse {
+    log::error!("{}", err);
+
+    let resp = builder.protobuf(ErrorResponse::from(&err));
+    if resp.is_err() {
+        log::error!("can't cast the error to ErrorResponse");
+        return HttpResponse::InternalServerError().finish();
+    }
+    resp.unwrap()
+}
+
+/// `PartyIndex` for the first key of `parts` that isn't the index of one of the `members` public keys
+fn check_parts<T>(parts: &HashMap<usize, T>, members: usize) -> Result<(), Error> {
+    match parts.keys().find(|&&idx| idx >= members) {
+        Some(&id) => Err(Error::PartyIndex { id, n: members }),
+        None => Ok(()),
+    }
+}
+
+/// The nullified dummy party's key for slot 0 of a committee of `n` parties
+fn dummy_pk<E: CurveId>(datum: &Data<E>, n: usize) -> Result<PublicKey<E>, Error> {
+    let mut sk_zero: SecretKey<E> = SecretKey::new(&mut OsRng);
+    sk_zero.nullify();
+
+    get_pk_exp(&sk_zero, 0, domain_size(n), datum.lagrange_helper(n)?)
+}
+
+/// The committee of a request with threshold `t`: the registered one it references, or else the one
+/// of the `n - 1` keys in `pks`
+#[allow(clippy::too_many_arguments)]
+async fn request_committee<E: CurveId>(
+    datum: &Data<E>,
+    reference: Option<&CommitteeRef>,
+    pks: Vec<PublicKey<E>>,
+    n: usize,
+    t: usize,
+    weights: Option<WeightedCommittee>,
+    check: bool,
+) -> Result<Arc<Committee<E>>, HttpResponse> {
+    if let Some(reference) = reference {
+        let committee = datum.committees.get(reference)
+            .map_err(|err| error_response(HttpResponse::NotFound(), err))?;
+        if t >= committee.n {
+            return Err(error_response(HttpResponse::BadRequest(), Error::Threshold { t, n: committee.n }));
+        }
+        return Ok(committee);
+    }
+
+    check_committee(n, t, pks.len()).map_err(|err| error_response(HttpResponse::BadRequest(), err))?;
+    build_committee(datum, String::new(), pks, n, weights, check).await.map(Arc::new)
+}
+
+/// The registered committee with `id`, or with `hash` if `id` is empty
+fn registered_committee<E: CurveId>(datum: &Data<E>, id: String, hash: Vec<u8>) -> Result<Arc<Committee<E>>, Error> {
+    CommitteeRef::from_request(id, hash)
+        .ok_or(Error::UnknownCommittee(String::new()))
+        .and_then(|reference| datum.committees.get(&reference))
+}
+
+/// Builds the committee of the `n - 1` keys in `pks`, with `check` their hints go through
+/// `check_pks` first
+async fn build_committee<E: CurveId>(
+    datum: &Data<E>,
+    id: String,
+    pks: Vec<PublicKey<E>>,
+    n: usize,
+    weights: Option<WeightedCommittee>,
+    check: bool,
+) -> Result<Committee<E>, HttpResponse> {
+    if check {
+        let mut slot_pks = pks.clone();
+        match dummy_pk(datum, n) {
+            Ok(pk_zero) => slot_pks.insert(0, pk_zero),
+            Err(err) => return Err(error_response(HttpResponse::BadRequest(), err)),
+        }
+        if let Some(resp) = check_pks(datum, &slot_pks, n, weights.as_ref()).await {
+            return Err(resp);
+        }
+    }
+
+    datum.lagrange_helper(n)
+        .and_then(|lagrange_helper| Committee::new(id, pks, n, weights, lagrange_helper, &datum.kzg_setup))
+        .map_err(|err| error_response(HttpResponse::BadRequest(), err))
+}
+
+/// Maps request indices (slot - 1) to the parties of `weights` holding them, or keeps them as they
+/// are for an unweighted committee
+fn party_indices(indices: &[usize], weights: Option<&WeightedCommittee>) -> Vec<u64> {
+    let mut parties: Vec<u64> = indices.iter()
+        .map(|&idx| weights.and_then(|committee| committee.party(idx + 1)).unwrap_or(idx) as u64)
+        .collect();
+    parties.dedup();
+    parties
+}
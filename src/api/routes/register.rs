@@ -0,0 +1,51 @@
+use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
+use actix_web::{HttpRequest, HttpResponse};
+
+use crate::error::{check_committee, Error};
+
+use crate::api::types::*;
+use crate::curve::CurveId;
+
+use super::{build_committee, error_response};
+
+pub async fn register_route<E: CurveId>(config: HttpRequest, data: ProtoBuf<RegisterRequest>) -> HttpResponse {
+    let datum = config.app_data::<Data<E>>().unwrap();
+
+    let params = match data.0.deserialize::<E>() {
+        Ok(params) => params,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
+
+    if let Err(err) = check_committee(params.n, 0, params.pks.len()) {
+        return error_response(HttpResponse::BadRequest(), err);
+    }
+    // before the hints are checked, which is most of the work
+    if let Err(err) = datum.committees.check_full() {
+        return error_response(HttpResponse::InsufficientStorage(), err);
+    }
+
+    // the hints are checked once here, whatever check_pks is set to, and never again for this committee
+    let mut committee = match build_committee(datum, params.id, params.pks, params.n, params.weights, true).await {
+        Ok(committee) => committee,
+        Err(resp) => return resp,
+    };
+    if committee.id.is_empty() {
+        committee.id = hex::encode(committee.hash);
+    }
+
+    let committee = match datum.committees.insert(committee).await {
+        Ok(committee) => committee,
+        Err(err @ Error::CommitteeExists(_)) => return error_response(HttpResponse::Conflict(), err),
+        Err(err @ Error::RegistryFull(_)) => return error_response(HttpResponse::InsufficientStorage(), err),
+        Err(err @ Error::Storage(_)) => return error_response(HttpResponse::InternalServerError(), err),
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
+    log::info!("registered committee {} of {} parties", committee.id, committee.n);
+
+    let resp = HttpResponse::Ok().protobuf(RegisterResponse { id: committee.id.clone(), hash: committee.hash.to_vec() });
+    if resp.is_err() {
+        log::error!("can't cast the result to RegisterResponse");
+        return HttpResponse::InternalServerError().finish();
+    }
+    resp.unwrap()
+}
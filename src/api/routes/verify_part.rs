@@ -1,25 +1,55 @@
-use actix_protobuf::ProtoBuf;
+use actix_protobuf::{ProtoBuf, ProtoBufResponseBuilder};
 use actix_web::{HttpRequest, HttpResponse};
 
-use crate::decryption::part_verify;
+use crate::decryption::{invalid_parts, part_verify, verify_part_dec};
 
 use crate::api::types::*;
+use crate::curve::CurveId;
 
-pub async fn verify_part_route(config: HttpRequest, data: ProtoBuf<VerifyPartRequest>) -> HttpResponse {
-    let datum = config.app_data::<Data>().unwrap();
+use super::{check_parts, error_response};
+
+pub async fn verify_part_route<E: CurveId>(config: HttpRequest, data: ProtoBuf<VerifyPartRequest>) -> HttpResponse {
+    let datum = config.app_data::<Data<E>>().unwrap();
     let kzg_setup = datum.kzg_setup.clone();
 
-    let verify_res = data.0.deserialize();
-    if verify_res.is_none() {
-        log::error!("can't deserialize decrypt params");
-        return HttpResponse::InternalServerError().finish();
-    }
-    let verify = verify_res.unwrap();
+    let verify = match data.0.deserialize::<E>() {
+        Ok(verify) => verify,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
 
-    let p = part_verify(verify.gamma_g2, &verify.pk, kzg_setup.powers_of_g[0].into(), verify.part_dec);
+    // a proof spares the two pairings
+    let p = match &verify.proof {
+        Some(proof) => verify_part_dec(&verify.pk.bls_pk, &verify.gamma_g2, &verify.part_dec, proof),
+        None => part_verify(verify.gamma_g2, &verify.pk, kzg_setup.powers_of_g[0].into(), verify.part_dec)
+    };
     if p == true {
         return HttpResponse::Ok().finish();
     }
 
     HttpResponse::UnavailableForLegalReasons().finish() // LOL
+}
+
+pub async fn batch_verify_part_route<E: CurveId>(data: ProtoBuf<BatchVerifyPartRequest>) -> HttpResponse {
+    let verify = match data.0.deserialize::<E>() {
+        Ok(verify) => verify,
+        Err(err) => return error_response(HttpResponse::BadRequest(), err),
+    };
+
+    if let Err(err) = check_parts(&verify.parts, verify.pks.len()) {
+        return error_response(HttpResponse::BadRequest(), err);
+    }
+
+    let invalid = invalid_parts(&verify.gamma_g2, &verify.parts, &verify.pks);
+    if invalid.is_empty() {
+        return HttpResponse::Ok().finish();
+    }
+    log::error!("invalid partial decryptions for parties {:?}", invalid);
+
+    let indices = invalid.iter().map(|&idx| idx as u64).collect();
+    let resp = HttpResponse::UnavailableForLegalReasons().protobuf(IndicesResponse { indices });
+    if resp.is_err() {
+        log::error!("can't cast the result to IndicesResponse");
+        return HttpResponse::InternalServerError().finish();
+    }
+    resp.unwrap()
 }
\ No newline at end of file
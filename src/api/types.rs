@@ -8,10 +8,15 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use block_modes::Cbc;
 use block_modes::block_padding::Pkcs7;
 
-use crate::encryption::Ciphertext;
+use crate::decryption::PartDecProof;
+use crate::dem::Dem;
+use crate::encryption::{Ciphertext, CiphertextProof};
+use crate::error::{check_parties, Error};
 use crate::kzg::UniversalParams;
 use crate::setup::{PublicKey, SecretKey};
-use crate::utils::LagrangePolyHelper;
+use crate::utils::{domain_size, IsValidHelper, LagrangePolyHelper};
+use crate::weighted::WeightedCommittee;
+use crate::api::registry::{CommitteeRef, Registry};
 
 use prost::{self, Message};
 
@@ -21,16 +26,40 @@ pub type G1 = <E as Pairing>::G1;
 pub type Aes256Cbc = Cbc<Aes256, Pkcs7>;
 
 #[derive(Clone)]
-pub struct Data {
+pub struct Data<E: Pairing = Bls12_381> {
     pub kzg_setup: UniversalParams<E>,
     pub sk: SecretKey<E>,
-    pub lagrange_helpers: Vec<LagrangePolyHelper>
+    /// helpers keyed by the domain size they were built for
+    pub lagrange_helpers: HashMap<usize, LagrangePolyHelper<E>>,
+    pub is_valid_helpers: HashMap<usize, IsValidHelper<E>>,
+    pub check_pks: bool,
+    /// refuse /partdec and /decrypt requests that don't carry a `CiphertextProof`, tags aside
+    pub require_proof: bool,
+    /// refuse /partdec requests that don't carry a `requester_pk`
+    pub require_requester: bool,
+    pub committees: Registry<E>
+}
+
+impl<E: Pairing> Data<E> {
+    /// lagrange helper of a committee of `n` parties, the one of `domain_size(n)`
+    pub fn lagrange_helper(&self, n: usize) -> Result<&LagrangePolyHelper<E>, Error> {
+        check_parties(n)?;
+        let size = domain_size(n);
+        self.lagrange_helpers.get(&size).ok_or(Error::MissingHelper(size))
+    }
+
+    /// is_valid helper of a committee of `n` parties, the one of `domain_size(n)`
+    pub fn is_valid_helper(&self, n: usize) -> Result<&IsValidHelper<E>, Error> {
+        check_parties(n)?;
+        let size = domain_size(n);
+        self.is_valid_helpers.get(&size).ok_or(Error::MissingHelper(size))
+    }
 }
 
 // IsValid
 
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
-pub struct IsValid {
+pub struct IsValid<E: Pairing = Bls12_381> {
     pub pk: PublicKey<E>,
     pub n: usize
 }
@@ -46,10 +75,11 @@ pub struct IsValidRequest {
 // VerifyPart
 
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
-pub struct VerifyPart {
-    pub gamma_g2: G2,
+pub struct VerifyPart<E: Pairing = Bls12_381> {
+    pub gamma_g2: E::G2,
     pub pk: PublicKey<E>,
-    pub part_dec: G2
+    pub part_dec: E::G2,
+    pub proof: Option<PartDecProof<E>>
 }
 
 #[derive(Clone, Eq, PartialEq, Message)]
@@ -59,17 +89,47 @@ pub struct VerifyPartRequest {
     #[prost(bytes, tag="2")]
     pub gamma_g2: Vec<u8>,
     #[prost(bytes, tag="3")]
-    pub part_dec: Vec<u8>
+    pub part_dec: Vec<u8>,
+    #[prost(bytes, tag="4")]
+    pub proof: Vec<u8>
+}
+
+// BatchVerifyPart
+
+#[derive(Clone)]
+pub struct BatchVerifyPart<E: Pairing = Bls12_381> {
+    pub gamma_g2: E::G2,
+    pub pks: Vec<PublicKey<E>>,
+    pub parts: HashMap<usize, E::G2>
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct BatchVerifyPartRequest {
+    #[prost(bytes, tag="1")]
+    pub gamma_g2: Vec<u8>,
+    #[prost(bytes, repeated, tag="2")]
+    pub pks: Vec<Vec<u8>>,
+    #[prost(map = "uint64, bytes", tag="3")]
+    pub parts: HashMap<u64, Vec<u8>>
 }
 
 // Encrypt
 
 #[derive(Clone)]
-pub struct Encrypt {
+pub struct Encrypt<E: Pairing = Bls12_381> {
     pub msg: Vec<u8>,
     pub pks: Vec<PublicKey<E>>,
     pub t: usize,
-    pub n: usize
+    pub n: usize,
+    pub check_pks: bool,
+    pub dem: Dem,
+    pub envelope: bool,
+    pub cca: bool,
+    pub weights: Option<WeightedCommittee>,
+    /// a registered committee, in place of `pks`, `n` and `weights`
+    pub committee: Option<CommitteeRef>,
+    /// encrypt for this tag, see `encrypt_for_tag`
+    pub tag: Option<Vec<u8>>
 }
 
 #[derive(Clone, PartialEq, Eq, Message)]
@@ -81,7 +141,23 @@ pub struct EncryptRequest {
     #[prost(uint64, tag="3")]
     pub t: u64,
     #[prost(uint64, tag="4")]
-    pub n: u64
+    pub n: u64,
+    #[prost(bool, tag="5")]
+    pub check_pks: bool,
+    #[prost(uint32, tag="6")]
+    pub dem: u32,
+    #[prost(bool, tag="7")]
+    pub envelope: bool,
+    #[prost(bool, tag="8")]
+    pub cca: bool,
+    #[prost(uint64, repeated, tag="9")]
+    pub weights: Vec<u64>,
+    #[prost(string, tag="10")]
+    pub committee_id: String,
+    #[prost(bytes, tag="11")]
+    pub committee_hash: Vec<u8>,
+    #[prost(bytes, tag="12")]
+    pub tag: Vec<u8>
 }
 
 #[derive(Clone, PartialEq, Eq, Message)]
@@ -95,11 +171,17 @@ pub struct EncryptResponse {
     #[prost(bytes, tag="4")]
     pub iv: Vec<u8>,
     #[prost(bytes, tag="5")]
-    pub gamma_g2: Vec<u8>
+    pub gamma_g2: Vec<u8>,
+    #[prost(uint32, tag="6")]
+    pub dem: u32,
+    #[prost(bytes, tag="7")]
+    pub envelope: Vec<u8>,
+    #[prost(bytes, tag="8")]
+    pub proof: Vec<u8>
 }
 
 impl EncryptResponse {
-    pub fn new(enc: Vec<u8>, ct: Ciphertext<E>, iv: Vec<u8>) -> Self {
+    pub fn new<E: Pairing>(enc: Vec<u8>, ct: Ciphertext<E>, proof: Option<CiphertextProof<E>>, iv: Vec<u8>, dem: Dem, envelope: Vec<u8>) -> Self {
         let mut sa1 = Vec::new();
         let mut res = ct.sa1.serialize_compressed(&mut sa1);
         if res.is_err() {
@@ -118,12 +200,23 @@ impl EncryptResponse {
             log::error!("can't serialize sa1");
         }
 
+        let mut proof_bytes = Vec::new();
+        if let Some(proof) = proof {
+            res = proof.serialize_compressed(&mut proof_bytes);
+            if res.is_err() {
+                log::error!("can't serialize proof");
+            }
+        }
+
         EncryptResponse {
             enc,
             sa1,
             sa2,
             iv,
-            gamma_g2
+            gamma_g2,
+            dem: dem as u32,
+            envelope,
+            proof: proof_bytes
         }
     }
 }
@@ -132,16 +225,26 @@ impl EncryptResponse {
 // DecryptParams
 
 #[derive(Clone)]
-pub struct Decrypt {
+pub struct Decrypt<E: Pairing = Bls12_381> {
     pub enc: Vec<u8>,
     pub pks: Vec<PublicKey<E>>,
-    pub parts: HashMap<usize, G2>,
-    pub gamma_g2: G2,
-    pub sa1: [G1; 2],
-    pub sa2: [G2; 6],
+    pub parts: HashMap<usize, E::G2>,
+    pub gamma_g2: E::G2,
+    pub sa1: [E::G1; 2],
+    pub sa2: [E::G2; 6],
     pub iv: Vec<u8>,
+    /// associated data of `enc`, the header or everything before the nonce of an envelope
+    pub ad: Vec<u8>,
     pub n: usize,
-    pub t: usize
+    pub t: usize,
+    pub check_pks: bool,
+    pub dem: Dem,
+    pub proof: Option<CiphertextProof<E>>,
+    /// the ciphertext was encrypted for a tag, whose `gamma_g2` it has, so it can't have a proof
+    pub tagged: bool,
+    pub weights: Option<WeightedCommittee>,
+    /// a registered committee, in place of `pks`, `n` and `weights`
+    pub committee: Option<CommitteeRef>
 }
 
 #[derive(Clone, PartialEq, Eq, Message)]
@@ -163,20 +266,93 @@ pub struct DecryptRequest {
     #[prost(uint64, tag="8")]
     pub t: u64,
     #[prost(uint64, tag="9")]
-    pub n: u64
+    pub n: u64,
+    #[prost(bool, tag="10")]
+    pub check_pks: bool,
+    #[prost(uint32, tag="11")]
+    pub dem: u32,
+    #[prost(bytes, tag="12")]
+    pub envelope: Vec<u8>,
+    #[prost(bytes, tag="13")]
+    pub proof: Vec<u8>,
+    #[prost(uint64, repeated, tag="14")]
+    pub weights: Vec<u64>,
+    #[prost(string, tag="15")]
+    pub committee_id: String,
+    #[prost(bytes, tag="16")]
+    pub committee_hash: Vec<u8>,
+    #[prost(bytes, tag="17")]
+    pub tag: Vec<u8>
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct DecryptResponse {
+    #[prost(bytes, tag="1")]
+    pub result: Vec<u8>,
+    #[prost(uint64, repeated, tag="2")]
+    pub excluded: Vec<u64>
 }
 
 // PartDec
 
 #[derive(Clone)]
-pub struct PartDec {
-    pub gamma_g2: G2
+pub struct PartDec<E: Pairing = Bls12_381> {
+    pub gamma_g2: E::G2,
+    pub prove: bool,
+    /// the tag `gamma_g2` was hashed from, such requests are never checked
+    pub tag: Option<Vec<u8>>,
+    /// blind the partial decryption to this key, see `RequesterKey`
+    pub requester_pk: Option<E::G2>,
+    /// the rest of the ciphertext, the partial decryption is only issued if it is well formed
+    pub checked: Option<CheckedPartDec<E>>
+}
+
+#[derive(Clone)]
+pub struct CheckedPartDec<E: Pairing = Bls12_381> {
+    pub sa1: [E::G1; 2],
+    pub sa2: [E::G2; 6],
+    pub t: usize,
+    pub proof: CiphertextProof<E>,
+    /// the registered committee the proof is checked against
+    pub committee: CommitteeRef
 }
 
 #[derive(Clone, PartialEq, Eq, Message)]
 pub struct PartDecRequest {
     #[prost(bytes, tag="1")]
-    pub gamma_g2: Vec<u8>
+    pub gamma_g2: Vec<u8>,
+    #[prost(bytes, tag="2")]
+    pub sa1: Vec<u8>,
+    #[prost(bytes, tag="3")]
+    pub sa2: Vec<u8>,
+    #[prost(uint64, tag="4")]
+    pub t: u64,
+    #[prost(uint64, tag="5")]
+    pub n: u64,
+    #[prost(bytes, repeated, tag="6")]
+    pub pks: Vec<Vec<u8>>,
+    #[prost(bytes, tag="7")]
+    pub proof: Vec<u8>,
+    #[prost(bool, tag="8")]
+    pub prove: bool,
+    #[prost(uint64, repeated, tag="9")]
+    pub weights: Vec<u64>,
+    #[prost(string, tag="10")]
+    pub committee_id: String,
+    #[prost(bytes, tag="11")]
+    pub committee_hash: Vec<u8>,
+    #[prost(bytes, tag="12")]
+    pub tag: Vec<u8>,
+    #[prost(bytes, tag="13")]
+    pub requester_pk: Vec<u8>
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct PartDecResponse {
+    #[prost(bytes, tag="1")]
+    pub result: Vec<u8>,
+    #[prost(bytes, tag="2")]
+    pub proof: Vec<u8>
 }
 
 // PK
@@ -185,13 +361,70 @@ pub struct PKRequest {
     #[prost(uint64, tag="1")]
     pub id: u64,
     #[prost(uint64, tag="2")]
-    pub n: u64
+    pub n: u64,
+    #[prost(uint64, repeated, tag="3")]
+    pub weights: Vec<u64>
 }
 
 #[derive(Clone)]
 pub struct PK {
     pub id: usize,
-    pub n: usize
+    pub n: usize,
+    pub weights: Option<WeightedCommittee>
+}
+
+// Register
+
+#[derive(Clone)]
+pub struct Register<E: Pairing = Bls12_381> {
+    pub id: String,
+    pub pks: Vec<PublicKey<E>>,
+    pub n: usize,
+    pub weights: Option<WeightedCommittee>
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct RegisterRequest {
+    #[prost(string, tag="1")]
+    pub id: String,
+    #[prost(uint64, tag="2")]
+    pub n: u64,
+    #[prost(bytes, repeated, tag="3")]
+    pub pks: Vec<Vec<u8>>,
+    #[prost(uint64, repeated, tag="4")]
+    pub weights: Vec<u64>
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct RegisterResponse {
+    #[prost(string, tag="1")]
+    pub id: String,
+    #[prost(bytes, tag="2")]
+    pub hash: Vec<u8>
+}
+
+// AggKey
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct AggKeyRequest {
+    #[prost(string, tag="1")]
+    pub committee_id: String,
+    #[prost(bytes, tag="2")]
+    pub committee_hash: Vec<u8>,
+    #[prost(bool, tag="3")]
+    pub hints: bool
+}
+
+// EncKey
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct EncKeyRequest {
+    #[prost(string, tag="1")]
+    pub committee_id: String,
+    #[prost(bytes, tag="2")]
+    pub committee_hash: Vec<u8>,
+    #[prost(uint64, tag="3")]
+    pub t: u64
 }
 
 // Result
@@ -200,4 +433,28 @@ pub struct PK {
 pub struct Response {
     #[prost(bytes, tag="1")]
     pub result: Vec<u8>
-}
\ No newline at end of file
+}
+
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct IndicesResponse {
+    #[prost(uint64, repeated, tag="1")]
+    pub indices: Vec<u64>
+}
+
+/// Body of the 4xx and 5xx responses, `code` is `Error::code`
+#[derive(Clone, PartialEq, Eq, Message)]
+pub struct ErrorResponse {
+    #[prost(uint32, tag="1")]
+    pub code: u32,
+    #[prost(string, tag="2")]
+    pub message: String
+}
+
+impl From<&Error> for ErrorResponse {
+    fn from(err: &Error) -> Self {
+        ErrorResponse {
+            code: err.code(),
+            message: err.to_string()
+        }
+    }
+}
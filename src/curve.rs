@@ -0,0 +1,153 @@
+use ark_bls12_377::Bls12_377;
+use ark_bls12_381::Bls12_381;
+use ark_bn254::{Bn254, Fq, Fq2};
+use ark_ec::{pairing::Pairing, short_weierstrass::Affine, AffineRepr};
+use ark_ff::PrimeField;
+use ark_serialize::*;
+use sha2::{Digest, Sha512};
+
+use crate::hash_to_curve::{hash_to_curve, hash_to_g2};
+
+/// Identifies the pairing a serialized artifact was produced on
+pub trait CurveId: Pairing {
+    const CURVE_ID: u8;
+    const NAME: &'static str;
+
+    /// Hashes `msg` to a point of G2 whose discrete log nobody knows, `dst` separates the uses. This is
+    /// the `_XMD:SHA-256_SSWU_RO_` suite of RFC 9380 on the BLS curves, see `hash_to_curve`
+    fn hash_to_g2(dst: &[u8], msg: &[u8]) -> Self::G2;
+}
+
+impl CurveId for Bls12_381 {
+    const CURVE_ID: u8 = 1;
+    const NAME: &'static str = "bls12-381";
+
+    fn hash_to_g2(dst: &[u8], msg: &[u8]) -> Self::G2 {
+        hash_to_g2(dst, msg)
+    }
+}
+
+impl CurveId for Bls12_377 {
+    const CURVE_ID: u8 = 2;
+    const NAME: &'static str = "bls12-377";
+
+    fn hash_to_g2(dst: &[u8], msg: &[u8]) -> Self::G2 {
+        hash_to_curve::<ark_bls12_377::g2::Config>(dst, msg)
+    }
+}
+
+impl CurveId for Bn254 {
+    const CURVE_ID: u8 = 3;
+    const NAME: &'static str = "bn254";
+
+    /// arkworks has no map to the G2 of BN254, so x is hashed with a counter until it is on the curve.
+    /// It doesn't run in constant time, which is fine for the public messages this is used for.
+    fn hash_to_g2(dst: &[u8], msg: &[u8]) -> Self::G2 {
+        let hash_to_fq = |counter: u32, coordinate: u8| {
+            let digest = Sha512::new()
+                .chain_update(dst)
+                .chain_update([dst.len() as u8, coordinate])
+                .chain_update(counter.to_be_bytes())
+                .chain_update(msg)
+                .finalize();
+            Fq::from_be_bytes_mod_order(&digest)
+        };
+
+        let mut counter = 0;
+        loop {
+            let x = Fq2::new(hash_to_fq(counter, 0), hash_to_fq(counter, 1));
+            if let Some(point) = Affine::<ark_bn254::g2::Config>::get_point_from_x_unchecked(x, false) {
+                let point = point.clear_cofactor();
+                if !point.is_zero() {
+                    return point.into_group();
+                }
+            }
+            counter += 1;
+        }
+    }
+}
+
+pub const FILE_MAGIC: &[u8; 4] = b"STK\0";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FileError {
+    /// The file was written for another curve
+    WrongCurve(u8),
+    /// The file can't be deserialized
+    Malformed,
+}
+
+/// Curve a file was written for, files without `FILE_MAGIC` predate the tag and are BLS12-381 ones
+pub fn file_curve(bytes: &[u8]) -> u8 {
+    split_file(bytes).0
+}
+
+fn split_file(bytes: &[u8]) -> (u8, &[u8]) {
+    match bytes.strip_prefix(FILE_MAGIC) {
+        Some(rest) if !rest.is_empty() => (rest[0], &rest[1..]),
+        _ => (Bls12_381::CURVE_ID, bytes),
+    }
+}
+
+/// Serializes a transcript, key or helper as `FILE_MAGIC`, `CURVE_ID` and the compressed value
+pub fn write_file<E: CurveId, T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(FILE_MAGIC.len() + 1 + value.compressed_size());
+    bytes.extend_from_slice(FILE_MAGIC);
+    bytes.push(E::CURVE_ID);
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+/// Reads what `write_file` wrote, or an untagged BLS12-381 file
+pub fn read_file<E: CurveId, T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, FileError> {
+    let (curve, value) = split_file(bytes);
+    if curve != E::CURVE_ID {
+        return Err(FileError::WrongCurve(curve));
+    }
+
+    T::deserialize_compressed(value).map_err(|_| FileError::Malformed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setup::SecretKey;
+    use ark_ec::Group;
+    use ark_std::Zero;
+
+    #[test]
+    fn test_file() {
+        let mut rng = ark_std::test_rng();
+
+        let sk = SecretKey::<Bn254>::new(&mut rng);
+        let bytes = write_file::<Bn254, _>(&sk);
+        assert_eq!(file_curve(&bytes), Bn254::CURVE_ID);
+        assert_eq!(read_file::<Bn254, SecretKey<Bn254>>(&bytes).unwrap().sk, sk.sk);
+        assert_eq!(read_file::<Bls12_377, SecretKey<Bls12_377>>(&bytes).err(), Some(FileError::WrongCurve(Bn254::CURVE_ID)));
+
+        // untagged files are BLS12-381 ones
+        let sk = SecretKey::<Bls12_381>::new(&mut rng);
+        let mut bytes = Vec::new();
+        sk.serialize_compressed(&mut bytes).unwrap();
+        assert_eq!(read_file::<Bls12_381, SecretKey<Bls12_381>>(&bytes).unwrap().sk, sk.sk);
+        assert_eq!(read_file::<Bn254, SecretKey<Bn254>>(&bytes).err(), Some(FileError::WrongCurve(Bls12_381::CURVE_ID)));
+
+        assert_eq!(read_file::<Bls12_381, SecretKey<Bls12_381>>(FILE_MAGIC).err(), Some(FileError::Malformed));
+    }
+
+    fn check_hash_to_g2<E: CurveId>() {
+        let point = E::hash_to_g2(b"dst", b"tag");
+        assert!(point == E::hash_to_g2(b"dst", b"tag"));
+        assert!(point != E::hash_to_g2(b"dst", b"other tag"));
+        assert!(point != E::hash_to_g2(b"other dst", b"tag"));
+        assert!(!point.is_zero());
+        assert!(point.mul_bigint(E::ScalarField::MODULUS).is_zero());
+    }
+
+    #[test]
+    fn test_hash_to_g2() {
+        check_hash_to_g2::<Bls12_381>();
+        check_hash_to_g2::<Bls12_377>();
+        check_hash_to_g2::<Bn254>();
+    }
+}
@@ -1,18 +1,26 @@
-use ark_bls12_381::{Bls12_381, Config};
 use ark_ec::{
-    bls12::{Bls12, G1Prepared, G2Prepared}, pairing::{Pairing, PairingOutput}, VariableBaseMSM
+    pairing::{Pairing, PairingOutput}, VariableBaseMSM
 };
 use ark_poly::{
     univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial,
     Radix2EvaluationDomain,
 };
-use ark_std::{One, Zero};
+use ark_ec::Group;
+use ark_ff::PrimeField;
+use ark_serialize::*;
+use ark_std::{rand::RngCore, One, UniformRand, Zero};
+use rand::rngs::OsRng;
+use sha2::{Digest, Sha512};
+use std::collections::HashMap;
 use std::ops::Div;
 
 use crate::{
-    api::types::{E, G1, G2}, kzg::{UniversalParams, KZG10}, setup::{AggregateKey, PublicKey}, utils::{interp_mostly_zero, IsValidHelper}
+    encryption::{verify_ciphertext, CiphertextProof}, error::{check_parties, Error}, kzg::{check_degree_is_too_large, UniversalParams, KZG10}, setup::{AggregateKey, PublicKey}, utils::{interp_mostly_zero, spawn, IsValidHelper}
 };
 
+/// `partial_decryptions` and `selector` hold the entries of the `n` parties, slot 0 being the dummy
+/// party. The padding slots of `agg_key` are selected with the dummy party's share and `t` is raised
+/// by their number, as in `encrypt`.
 pub async fn agg_dec<E: Pairing>(
     partial_decryptions: &[E::G2], //insert 0 if a party did not respond or verification failed
     sa1: &[E::G1; 2],
@@ -22,20 +30,93 @@ pub async fn agg_dec<E: Pairing>(
     selector: &[bool],
     agg_key: &AggregateKey<E>,
     params: &UniversalParams<E>,
-) -> PairingOutput<E> {
+) -> Result<PairingOutput<E>, Error> {
+    let keys = agg_dec_batch(partial_decryptions, &[(*sa1, *sa2)], t, n, selector, agg_key, params).await?;
+    Ok(keys[0])
+}
+
+/// `sa1` and `sa2` of a ciphertext, what `agg_dec_batch` needs of each one
+pub type KemHeader<E> = ([<E as Pairing>::G1; 2], [<E as Pairing>::G2; 6]);
+
+/// `agg_dec` for ciphertexts of threshold `t` that share their `gamma_g2`, like the ones of
+/// `encrypt_for_tag` for the same tag, given by their `sa1` and `sa2`. Everything but the final
+/// pairing only depends on the partial decryptions, so it is done once for all of them.
+#[allow(clippy::too_many_arguments)]
+pub async fn agg_dec_batch<E: Pairing>(
+    partial_decryptions: &[E::G2],
+    headers: &[KemHeader<E>],
+    t: usize,
+    n: usize,
+    selector: &[bool],
+    agg_key: &AggregateKey<E>,
+    params: &UniversalParams<E>,
+) -> Result<Vec<PairingOutput<E>>, Error> {
+    let (w1, w2) = aggregate_proof(partial_decryptions, t, n, selector, agg_key, params).await?;
+
+    let enc_keys = headers.iter().map(|(sa1, sa2)| {
+        let mut enc_key_lhs = w1.to_vec();
+        enc_key_lhs.append(&mut sa1.to_vec());
+
+        let mut enc_key_rhs = sa2.to_vec();
+        enc_key_rhs.append(&mut w2.to_vec());
+
+        E::multi_pairing(enc_key_lhs, enc_key_rhs)
+    }).collect();
+
+    Ok(enc_keys)
+}
+
+/// The elements `agg_dec` pairs with the header of a ciphertext, `e(w1 || sa1, sa2 || w2)`: the
+/// aggregate of the selected partial decryptions along with the proof that the selected parties meet
+/// the threshold, the same one as in hinTS. `signature` uses it to aggregate signatures.
+pub(crate) async fn aggregate_proof<E: Pairing>(
+    partial_decryptions: &[E::G2],
+    t: usize,
+    n: usize,
+    selector: &[bool],
+    agg_key: &AggregateKey<E>,
+    params: &UniversalParams<E>,
+) -> Result<([E::G1; 6], [E::G2; 2]), Error> {
+    check_parties(n)?;
+    if t >= n {
+        return Err(Error::Threshold { t, n });
+    }
+    if selector.len() != n {
+        return Err(Error::SizeMismatch { what: "selector entries", expected: n, got: selector.len() });
+    }
+    if partial_decryptions.len() != n {
+        return Err(Error::SizeMismatch { what: "partial decryptions", expected: n, got: partial_decryptions.len() });
+    }
+    if agg_key.n != n {
+        return Err(Error::SizeMismatch { what: "parties", expected: agg_key.n, got: n });
+    }
+    agg_key.check_hints()?;
+    let padding = agg_key.padding();
+    check_degree_is_too_large(t + padding, params.powers_of_g.len())?;
+
+    // from here on n and t are the ones of the padded domain
+    let mut selector = selector.to_vec();
+    selector.resize(n + padding, true);
+    let mut partial_decryptions = partial_decryptions.to_vec();
+    partial_decryptions.resize(n + padding, partial_decryptions[0]);
+    let (t, n) = (t + padding, n + padding);
+
     let domain = Radix2EvaluationDomain::<E::ScalarField>::new(n).unwrap();
     let domain_elements: Vec<E::ScalarField> = domain.elements().collect();
     // points is where B is set to zero
     // parties is the set of parties who have signed
     let mut points = vec![domain_elements[0]]; // 0 is the dummy party that is always true
-    let mut parties: Vec<usize> = Vec::new(); // parties indexed from 0..n-1
-    for i in 0..n {
+    let mut parties: Vec<usize> = vec![0]; // parties indexed from 0..n-1
+    for i in 1..n {
         if selector[i] {
             parties.push(i);
         } else {
             points.push(domain_elements[i]);
         }
     }
+    if parties.len() < t + 1 {
+        return Err(Error::NotEnoughParts { t: t - padding, got: parties.len() - 1 - padding });
+    }
 
     let b = interp_mostly_zero(E::ScalarField::one(), &points);
     let b_evals = domain.fft(&b.coeffs);
@@ -46,10 +127,9 @@ pub async fn agg_dec<E: Pairing>(
     let b_async = b.clone();
     let params_async = params.clone();
     // commit to b in g2
-    let b_g2_task = tokio::spawn(async move {
+    let b_g2_task = spawn(async move {
         // let b_g2: E::G2 = 
         KZG10::<E, DensePolynomial<E::ScalarField>>::commit_g2(&params_async, &b_async)
-            .unwrap()
     });
     // let b_g2_output = tokio::join!(b_g2_task);
 
@@ -63,8 +143,7 @@ pub async fn agg_dec<E: Pairing>(
         DensePolynomial::from_coefficients_vec(vec![-domain_elements[0], E::ScalarField::one()]);
     let q0 = bminus1.div(&xminus1);
 
-    let q0_g1: E::G1 = KZG10::<E, DensePolynomial<E::ScalarField>>::commit_g1(params, &q0)
-        .unwrap()
+    let q0_g1: E::G1 = KZG10::<E, DensePolynomial<E::ScalarField>>::commit_g1(params, &q0)?
         .into();
 
     // bhat = x^t * b
@@ -74,7 +153,7 @@ pub async fn agg_dec<E: Pairing>(
     let agg_key_async = agg_key.clone();
     let params_async = params.clone();
 
-    let bhat_g1_task = tokio::spawn(async move {
+    let bhat_g1_task = spawn(async move {
         let mut bhat_coeffs = vec![E::ScalarField::zero(); t];
         bhat_coeffs.append(&mut b_async.coeffs.clone());
         let bhat = DensePolynomial::from_coefficients_vec(bhat_coeffs);
@@ -82,7 +161,6 @@ pub async fn agg_dec<E: Pairing>(
 
         // let bhat_g1: E::G1 =
         KZG10::<E, DensePolynomial<E::ScalarField>>::commit_g1(&params_async, &bhat)
-            .unwrap()
     });
     // let bhat_g1_output = tokio::join!(bhat_g1_task);
 
@@ -92,7 +170,7 @@ pub async fn agg_dec<E: Pairing>(
     let parties_async = parties.clone();
 
     // compute the aggregate public key
-    let apk_task = tokio::spawn(async move {
+    let apk_task = spawn(async move {
         let mut bases: Vec<<E as Pairing>::G1Affine> = Vec::new();
         let mut scalars: Vec<<E as Pairing>::ScalarField> = Vec::new();
         for &i in &parties_async {
@@ -106,10 +184,10 @@ pub async fn agg_dec<E: Pairing>(
     // let apk_output = tokio::join!(apk_task);
 
     let b_evals_async = b_evals.clone();
-    let partial_decryptions_async = partial_decryptions.to_vec().clone();
+    let partial_decryptions_async = partial_decryptions.clone();
     let parties_async = parties.clone();
 
-    let sigma_task = tokio::spawn(async move {
+    let sigma_task = spawn(async move {
         // compute sigma = (\sum B(omega^i)partial_decryptions[i])/(n) for i in parties
         let mut bases: Vec<<E as Pairing>::G2Affine> = Vec::new();
         let mut scalars: Vec<<E as Pairing>::ScalarField> = Vec::new();
@@ -126,7 +204,7 @@ pub async fn agg_dec<E: Pairing>(
     let agg_key_async = agg_key.clone();
     let parties_async = parties.clone();
 
-    let qx_task = tokio::spawn(async move {
+    let qx_task = spawn(async move {
         // compute Qx, Qhatx and Qz
         let mut bases: Vec<<E as Pairing>::G1Affine> = Vec::new();
         let mut scalars: Vec<<E as Pairing>::ScalarField> = Vec::new();
@@ -141,7 +219,7 @@ pub async fn agg_dec<E: Pairing>(
     let b_evals_async = b_evals.clone();
     let parties_async = parties.clone();
 
-    let qz_task = tokio::spawn(async move {
+    let qz_task = spawn(async move {
         let mut bases: Vec<<E as Pairing>::G1Affine> = Vec::new();
         let mut scalars: Vec<<E as Pairing>::ScalarField> = Vec::new();
         for &i in &parties_async {
@@ -156,7 +234,7 @@ pub async fn agg_dec<E: Pairing>(
     let agg_key_async = agg_key.clone();
     let parties_async = parties.clone();
 
-    let qhatx_task = tokio::spawn(async move {
+    let qhatx_task = spawn(async move {
         let mut bases: Vec<<E as Pairing>::G1Affine> = Vec::new();
         let mut scalars: Vec<<E as Pairing>::ScalarField> = Vec::new();
         for &i in &parties_async {
@@ -174,36 +252,248 @@ pub async fn agg_dec<E: Pairing>(
         qz_task.await.unwrap() * (minus1),
         qx_task.await.unwrap().into() * (minus1),
         qhatx_task.await.unwrap(),
-        bhat_g1_task.await.unwrap() * (minus1),
+        bhat_g1_task.await.unwrap()? * (minus1),
         q0_g1 * (minus1),
     ];
-    let w2 = [b_g2_task.await.unwrap().into(), sigma_task.await.unwrap()];
+    let w2 = [b_g2_task.await.unwrap()?.into(), sigma_task.await.unwrap()];
+
+    Ok((w1, w2))
+}
+
+/// `agg_dec` for ciphertexts from `encrypt_cca`. The proof is checked before the partial decryptions
+/// are touched and `InvalidProof` is returned if it doesn't verify.
+#[allow(clippy::too_many_arguments)]
+pub async fn agg_dec_cca<E: Pairing>(
+    partial_decryptions: &[E::G2],
+    gamma_g2: &E::G2,
+    sa1: &[E::G1; 2],
+    sa2: &[E::G2; 6],
+    proof: &CiphertextProof<E>,
+    t: usize,
+    n: usize,
+    selector: &[bool],
+    agg_key: &AggregateKey<E>,
+    params: &UniversalParams<E>,
+) -> Result<PairingOutput<E>, Error> {
+    if !verify_ciphertext(gamma_g2, sa1, sa2, t, proof, agg_key, params) {
+        return Err(Error::InvalidProof);
+    }
+
+    agg_dec(partial_decryptions, sa1, sa2, t, n, selector, agg_key, params).await
+}
+
+pub fn part_verify<E: Pairing>(gamma_g2: E::G2, pk: &PublicKey<E>, g1: E::G1, part_dec: E::G2) -> bool {
+    E::pairing(pk.bls_pk, gamma_g2) == E::pairing(g1, part_dec)
+}
+
+/// Chaum-Pedersen proof that a partial decryption `sk * gamma_g2` uses the same `sk` as `bls_pk = sk * g1`
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PartDecProof<E: Pairing> {
+    pub c: E::ScalarField,
+    pub z: E::ScalarField,
+}
+
+/// A partial decryption along with what is needed to check it
+#[derive(Clone)]
+pub struct PartialDecryption<E: Pairing> {
+    pub bls_pk: E::G1,
+    pub gamma_g2: E::G2,
+    pub part_dec: E::G2,
+}
+
+const PART_DEC_DOMAIN: &[u8] = b"silent-threshold/partdec/v1";
+
+pub(crate) fn part_dec_challenge<E: Pairing>(
+    bls_pk: &E::G1,
+    gamma_g2: &E::G2,
+    part_dec: &E::G2,
+    r_g1: &E::G1,
+    r_g2: &E::G2,
+) -> E::ScalarField {
+    let mut bytes = Vec::new();
+    bls_pk.serialize_compressed(&mut bytes).unwrap();
+    gamma_g2.serialize_compressed(&mut bytes).unwrap();
+    part_dec.serialize_compressed(&mut bytes).unwrap();
+    r_g1.serialize_compressed(&mut bytes).unwrap();
+    r_g2.serialize_compressed(&mut bytes).unwrap();
+
+    let digest = Sha512::new()
+        .chain_update(PART_DEC_DOMAIN)
+        .chain_update(&bytes)
+        .finalize();
+
+    E::ScalarField::from_le_bytes_mod_order(&digest)
+}
+
+/// Checks a `PartDecProof`, only needs `bls_pk` and no pairing
+pub fn verify_part_dec<E: Pairing>(bls_pk: &E::G1, gamma_g2: &E::G2, part_dec: &E::G2, proof: &PartDecProof<E>) -> bool {
+    let r_g1 = E::G1::generator() * proof.z - *bls_pk * proof.c;
+    let r_g2 = *gamma_g2 * proof.z - *part_dec * proof.c;
+
+    proof.c == part_dec_challenge::<E>(bls_pk, gamma_g2, part_dec, &r_g1, &r_g2)
+}
+
+/// A partial decryption ElGamal-encrypted to the G2 key of a requester, `c1 = r * h` and
+/// `c2 = part_dec + r * requester_pk`. Whoever relays it learns nothing of `part_dec`, only the
+/// requester can `RequesterKey::unblind` it and finish `agg_dec`.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlindedPartDec<E: Pairing> {
+    pub c1: E::G2,
+    pub c2: E::G2,
+}
+
+/// Proof that a `BlindedPartDec` encrypts `sk * gamma_g2` for the `sk` of `bls_pk = sk * g1`
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlindedPartDecProof<E: Pairing> {
+    pub c: E::ScalarField,
+    /// responses for sk and r
+    pub z: [E::ScalarField; 2],
+}
+
+const BLINDED_PART_DEC_DOMAIN: &[u8] = b"silent-threshold/blinded-partdec/v1";
+
+pub(crate) fn blinded_part_dec_challenge<E: Pairing>(
+    bls_pk: &E::G1,
+    gamma_g2: &E::G2,
+    requester_pk: &E::G2,
+    blinded: &BlindedPartDec<E>,
+    r_g1: &E::G1,
+    r_g2: &[E::G2; 2],
+) -> E::ScalarField {
+    let mut bytes = Vec::new();
+    bls_pk.serialize_compressed(&mut bytes).unwrap();
+    gamma_g2.serialize_compressed(&mut bytes).unwrap();
+    requester_pk.serialize_compressed(&mut bytes).unwrap();
+    blinded.serialize_compressed(&mut bytes).unwrap();
+    r_g1.serialize_compressed(&mut bytes).unwrap();
+    r_g2.serialize_compressed(&mut bytes).unwrap();
+
+    let digest = Sha512::new()
+        .chain_update(BLINDED_PART_DEC_DOMAIN)
+        .chain_update(&bytes)
+        .finalize();
+
+    E::ScalarField::from_le_bytes_mod_order(&digest)
+}
+
+/// Checks a `BlindedPartDecProof`, only needs `bls_pk` and no pairing, so a relay can drop wrong
+/// shares without being able to read them
+pub fn verify_blinded_part_dec<E: Pairing>(
+    bls_pk: &E::G1,
+    gamma_g2: &E::G2,
+    requester_pk: &E::G2,
+    blinded: &BlindedPartDec<E>,
+    proof: &BlindedPartDecProof<E>,
+) -> bool {
+    let [z_sk, z_r] = proof.z;
+    let r_g1 = E::G1::generator() * z_sk - *bls_pk * proof.c;
+    let r_g2 = [
+        E::G2::generator() * z_r - blinded.c1 * proof.c,
+        *gamma_g2 * z_sk + *requester_pk * z_r - blinded.c2 * proof.c,
+    ];
 
-    let mut enc_key_lhs = w1.to_vec();
-    enc_key_lhs.append(&mut sa1.to_vec());
+    proof.c == blinded_part_dec_challenge::<E>(bls_pk, gamma_g2, requester_pk, blinded, &r_g1, &r_g2)
+}
 
-    let mut enc_key_rhs = sa2.to_vec();
-    enc_key_rhs.append(&mut w2.to_vec());
+/// Ephemeral key of a requester, the parties blind their partial decryptions to `pk`
+pub struct RequesterKey<E: Pairing> {
+    sk: E::ScalarField,
+    pub pk: E::G2,
+}
 
-    let enc_key = E::multi_pairing(enc_key_lhs, enc_key_rhs);
+impl<E: Pairing> RequesterKey<E> {
+    pub fn new<R: RngCore>(rng: &mut R) -> Self {
+        let sk = E::ScalarField::rand(rng);
+        RequesterKey { sk, pk: E::G2::generator() * sk }
+    }
 
-    enc_key
+    /// The partial decryption a `BlindedPartDec` holds
+    pub fn unblind(&self, blinded: &BlindedPartDec<E>) -> E::G2 {
+        blinded.c2 - blinded.c1 * self.sk
+    }
+}
+
+/// Checks every `e(bls_pk, gamma_g2) == e(g1, part_dec)` at once with a single multi-pairing over a random
+/// linear combination of the shares, shares of the same ciphertext share their `gamma_g2` pairing.
+/// A `false` doesn't tell which share is wrong.
+pub fn batch_part_verify<E: Pairing>(parts: &[PartialDecryption<E>]) -> bool {
+    let mut rng = OsRng;
+
+    let mut gammas: Vec<E::G2> = Vec::new();
+    let mut pks: Vec<E::G1> = Vec::new();
+    let mut part_dec = E::G2::zero();
+    for part in parts {
+        let rho = E::ScalarField::rand(&mut rng);
+        part_dec += part.part_dec * rho;
+
+        match gammas.iter().position(|gamma_g2| *gamma_g2 == part.gamma_g2) {
+            Some(idx) => pks[idx] += part.bls_pk * rho,
+            None => {
+                gammas.push(part.gamma_g2);
+                pks.push(part.bls_pk * rho);
+            }
+        }
+    }
+
+    pks.push(-E::G1::generator());
+    gammas.push(part_dec);
+
+    E::multi_pairing(pks, gammas).is_zero()
+}
+
+/// Indices of `parts` (party index to partial decryption of `gamma_g2`) that don't match the `bls_pk`
+/// of `pks[idx]`, sorted. The shares are checked with `batch_part_verify` and a failing batch is split
+/// in halves until the bad shares are found, so a handful of bad shares costs a few more multi-pairings.
+pub fn invalid_parts<E: Pairing>(gamma_g2: &E::G2, parts: &HashMap<usize, E::G2>, pks: &[PublicKey<E>]) -> Vec<usize> {
+    let mut invalid = Vec::new();
+    let mut batch: Vec<(usize, PartialDecryption<E>)> = Vec::new();
+    for (&idx, &part_dec) in parts {
+        match pks.get(idx) {
+            Some(pk) => batch.push((idx, PartialDecryption { bls_pk: pk.bls_pk, gamma_g2: *gamma_g2, part_dec })),
+            None => invalid.push(idx),
+        }
+    }
+
+    bisect_parts(&batch, &mut invalid);
+    invalid.sort_unstable();
+    invalid
 }
 
-pub fn part_verify(gamma_g2: <Bls12_381 as Pairing>::G2, pk: &PublicKey<Bls12_381>, g1: <Bls12_381 as Pairing>::G1, part_dec: <Bls12_381 as Pairing>::G2) -> bool {
-    Bls12_381::pairing(pk.bls_pk, gamma_g2) == Bls12_381::pairing(g1, part_dec)
+fn bisect_parts<E: Pairing>(batch: &[(usize, PartialDecryption<E>)], invalid: &mut Vec<usize>) {
+    if batch.is_empty() {
+        return;
+    }
+
+    let parts: Vec<PartialDecryption<E>> = batch.iter().map(|(_, part)| part.clone()).collect();
+    if batch_part_verify(&parts) {
+        return;
+    }
+
+    if batch.len() == 1 {
+        invalid.push(batch[0].0);
+        return;
+    }
+
+    let (left, right) = batch.split_at(batch.len() / 2);
+    bisect_parts(left, invalid);
+    bisect_parts(right, invalid);
 }
 
-fn prepare_and_pair(hint: G1, prepared_g2: &G2Prepared<Config>, prepared_bls_pk: &G1Prepared<Config>, li_x: G2) -> bool {
-    let prepared_hint = G1Prepared::from(hint);
-    let prepared_li_x = G2Prepared::from(li_x);
+fn prepare_and_pair<E: Pairing>(hint: E::G1, prepared_g2: &E::G2Prepared, prepared_bls_pk: &E::G1Prepared, li_x: E::G2) -> bool {
+    let prepared_hint = E::G1Prepared::from(hint);
+    let prepared_li_x = E::G2Prepared::from(li_x);
 
-    Bls12::pairing(prepared_hint, prepared_g2.clone()) == Bls12::pairing(prepared_bls_pk.clone(), prepared_li_x)
+    E::pairing(prepared_hint, prepared_g2.clone()) == E::pairing(prepared_bls_pk.clone(), prepared_li_x)
 }
 
-pub async fn is_valid(pk: &PublicKey<E>, n: usize, kzg_params: &UniversalParams<Bls12<ark_bls12_381::Config>>, helper: &IsValidHelper) -> bool {
-    let prepared_g2 = G2Prepared::from(kzg_params.powers_of_h[0]);
-    let prepared_bls_pk = G1Prepared::from(pk.bls_pk);
+pub async fn is_valid<E: Pairing>(pk: &PublicKey<E>, n: usize, kzg_params: &UniversalParams<E>, helper: &IsValidHelper<E>) -> bool {
+    // a hint set for another committee size or an out-of-range id can't be valid
+    if helper.li.len() != n || pk.id >= n || pk.sk_li_by_z.len() != n {
+        return false;
+    }
+
+    let prepared_g2 = E::G2Prepared::from(kzg_params.powers_of_h[0]);
+    let prepared_bls_pk = E::G1Prepared::from(pk.bls_pk);
 
     let mut tasks = Vec::new();
 
@@ -211,19 +501,19 @@ pub async fn is_valid(pk: &PublicKey<E>, n: usize, kzg_params: &UniversalParams<
     let li = helper.li[pk.id];
     let prepared_g2_async = prepared_g2.clone();
     let prepared_bls_pk_async = prepared_bls_pk.clone();
-    tasks.push(tokio::spawn(async move { prepare_and_pair(sk_li, &prepared_g2_async, &prepared_bls_pk_async, li) }));
+    tasks.push(spawn(async move { prepare_and_pair::<E>(sk_li, &prepared_g2_async, &prepared_bls_pk_async, li) }));
     
     let sk_li_minus0 = pk.sk_li_minus0;
     let li_minus0 = helper.li_minus0[pk.id];
     let prepared_g2_async = prepared_g2.clone();
     let prepared_bls_pk_async = prepared_bls_pk.clone();
-    tasks.push(tokio::spawn(async move { prepare_and_pair(sk_li_minus0, &prepared_g2_async, &prepared_bls_pk_async, li_minus0 )}));
+    tasks.push(spawn(async move { prepare_and_pair::<E>(sk_li_minus0, &prepared_g2_async, &prepared_bls_pk_async, li_minus0 )}));
     
     let sk_li_by_tau = pk.sk_li_by_tau;
     let li_by_tau = helper.li_by_tau[pk.id];
     let prepared_g2_async = prepared_g2.clone();
     let prepared_bls_pk_async = prepared_bls_pk.clone();
-    tasks.push(tokio::spawn(async move { prepare_and_pair(sk_li_by_tau, &prepared_g2_async, &prepared_bls_pk_async, li_by_tau) }));
+    tasks.push(spawn(async move { prepare_and_pair::<E>(sk_li_by_tau, &prepared_g2_async, &prepared_bls_pk_async, li_by_tau) }));
 
     for i in 0..n {
         let sk_li_by_z = pk.sk_li_by_z[i];
@@ -231,7 +521,7 @@ pub async fn is_valid(pk: &PublicKey<E>, n: usize, kzg_params: &UniversalParams<
         let prepared_g2_async = prepared_g2.clone();
         let prepared_bls_pk_async = prepared_bls_pk.clone();
         
-        tasks.push(tokio::spawn(async move { prepare_and_pair(sk_li_by_z, &prepared_g2_async, &prepared_bls_pk_async, li_by_z) }));
+        tasks.push(spawn(async move { prepare_and_pair::<E>(sk_li_by_z, &prepared_g2_async, &prepared_bls_pk_async, li_by_z) }));
     }
 
     for task in tasks {
@@ -243,23 +533,37 @@ pub async fn is_valid(pk: &PublicKey<E>, n: usize, kzg_params: &UniversalParams<
     true
 }
 
+/// Checks the public keys of a committee, where `pks[i]` is expected to hold the hints for slot `i`,
+/// and returns the slots whose key is for another slot or fails `is_valid`
+pub async fn invalid_pks<E: Pairing>(pks: &[PublicKey<E>], n: usize, kzg_params: &UniversalParams<E>, helper: &IsValidHelper<E>) -> Vec<usize> {
+    let mut invalid = Vec::new();
+    for (idx, pk) in pks.iter().enumerate() {
+        if pk.id != idx || !is_valid(pk, n, kzg_params, helper).await {
+            invalid.push(idx);
+        }
+    }
+
+    invalid
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        encryption::encrypt,
+        encryption::{encrypt, encrypt_cca, encrypt_for_tag},
         kzg::KZG10,
-        setup::{PublicKey, SecretKey}, utils::lagrange_poly,
+        setup::{get_pk_exp, PublicKey, SecretKey}, utils::{lagrange_poly, test_committee, LagrangePolyHelper, TestCommittee},
     };
     use ark_ec::bls12::Bls12;
     use ark_poly::univariate::DensePolynomial;
 
     type E = ark_bls12_381::Bls12_381;
+    type G1 = <E as Pairing>::G1;
     type G2 = <E as Pairing>::G2;
     type UniPoly381 = DensePolynomial<<E as Pairing>::ScalarField>;
 
-    #[test]
-    fn test_decryption() {
+    #[tokio::test]
+    async fn test_decryption() {
         let mut rng = ark_std::test_rng();
         let n = 16; // actually n-1 total parties. one party is a dummy party that is always true
         let t: usize = 15;
@@ -277,15 +581,15 @@ mod tests {
         // create the dummy party's keys
         sk.push(SecretKey::<E>::new(&mut rng));
         sk[0].nullify();
-        pk.push(sk[0].get_pk(0, &params, n, &lagrange_polys));
+        pk.push(sk[0].get_pk(0, &params, n, &lagrange_polys).await.unwrap());
 
         for i in 1..n {
             sk.push(SecretKey::<E>::new(&mut rng));
-            pk.push(sk[i].get_pk(i, &params, n, &lagrange_polys))
+            pk.push(sk[i].get_pk(i, &params, n, &lagrange_polys).await.unwrap())
         }
 
-        let agg_key = AggregateKey::<E>::new(pk, n, &params);
-        let ct = encrypt::<E>(&agg_key, t, &params);
+        let agg_key = AggregateKey::<E>::new(pk, n, &params).unwrap();
+        let ct = encrypt::<E>(&agg_key, t, &params).unwrap();
 
         // compute partial decryptions
         let mut partial_decryptions: Vec<G2> = Vec::new();
@@ -305,6 +609,244 @@ mod tests {
             selector.push(false);
         }
 
-        let _dec_key = agg_dec(&partial_decryptions, &ct.sa1, &ct.sa2, ct.t, n, &selector, &agg_key, &params);
+        let dec_key = agg_dec(&partial_decryptions, &ct.sa1, &ct.sa2, ct.t, n, &selector, &agg_key, &params).await.unwrap();
+        assert!(dec_key == ct.enc_key);
+    }
+
+    #[tokio::test]
+    async fn test_decryption_padded() {
+        let mut rng = ark_std::test_rng();
+        let n = 6; // the dummy party and 5 members, padded to 8 slots
+        let t: usize = 3;
+
+        let TestCommittee { params, sk, pk, agg_key, .. } = test_committee::<E>(n, &mut rng).await;
+
+        let ak = AggregateKey::<E>::new(pk, n, &params);
+        assert!(ak.err() == Some(Error::SizeMismatch { what: "public keys", expected: 8, got: n }));
+        assert_eq!(agg_key.padding(), 2);
+        let (ct, proof) = encrypt_cca::<E>(&agg_key, t, &params).unwrap();
+        assert_eq!(ct.t, t);
+
+        // the dummy party and the last t members
+        let mut partial_decryptions = vec![G2::zero(); n];
+        let mut selector = vec![false; n];
+        for i in (0..1).chain(n - t..n) {
+            partial_decryptions[i] = sk[i].partial_decryption(ct.gamma_g2);
+            selector[i] = true;
+        }
+
+        let dec_key = agg_dec_cca(&partial_decryptions, &ct.gamma_g2, &ct.sa1, &ct.sa2, &proof, ct.t, n, &selector, &agg_key, &params).await.unwrap();
+        assert!(dec_key == ct.enc_key);
+
+        // the padding doesn't count towards t
+        selector[n - 1] = false;
+        let dec_key = agg_dec(&partial_decryptions, &ct.sa1, &ct.sa2, ct.t, n, &selector, &agg_key, &params).await;
+        assert!(dec_key.err() == Some(Error::NotEnoughParts { t, got: t - 1 }));
+    }
+
+    #[tokio::test]
+    async fn test_decryption_tag() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t: usize = 4;
+
+        let TestCommittee { params, sk, agg_key, .. } = test_committee::<E>(n, &mut rng).await;
+
+        let cts: Vec<_> = (0..3).map(|_| encrypt_for_tag::<E>(&agg_key, t, &params, b"block 42").unwrap()).collect();
+        assert!(cts[0].gamma_g2 == cts[1].gamma_g2 && cts[0].enc_key != cts[1].enc_key);
+
+        // one partial decryption of the tag per party
+        let mut partial_decryptions = vec![G2::zero(); n];
+        let mut selector = vec![false; n];
+        for i in 0..t + 1 {
+            partial_decryptions[i] = sk[i].partial_decryption_for_tag(b"block 42");
+            selector[i] = true;
+        }
+
+        let headers: Vec<KemHeader<E>> = cts.iter().map(|ct| (ct.sa1, ct.sa2)).collect();
+        let dec_keys = agg_dec_batch(&partial_decryptions, &headers, t, n, &selector, &agg_key, &params).await.unwrap();
+        for (dec_key, ct) in dec_keys.iter().zip(cts.iter()) {
+            assert!(*dec_key == ct.enc_key);
+        }
+
+        let other = encrypt_for_tag::<E>(&agg_key, t, &params, b"block 43").unwrap();
+        let dec_key = agg_dec(&partial_decryptions, &other.sa1, &other.sa2, t, n, &selector, &agg_key, &params).await.unwrap();
+        assert!(dec_key != other.enc_key);
+    }
+
+    #[test]
+    fn test_part_dec_proof() {
+        let mut rng = ark_std::test_rng();
+
+        let sk: Vec<SecretKey<E>> = (0..4).map(|_| SecretKey::<E>::new(&mut rng)).collect();
+        let bls_pks: Vec<G1> = sk.iter().map(|sk| G1::generator() * sk.sk).collect();
+        let gammas = [G2::rand(&mut rng), G2::rand(&mut rng)];
+
+        let mut parts: Vec<PartialDecryption<E>> = Vec::new();
+        for (i, sk) in sk.iter().enumerate() {
+            let gamma_g2 = gammas[i % 2];
+            let (part_dec, proof) = sk.partial_decryption_with_proof(gamma_g2);
+            assert!(verify_part_dec(&bls_pks[i], &gamma_g2, &part_dec, &proof));
+            assert!(!verify_part_dec(&bls_pks[(i + 1) % 4], &gamma_g2, &part_dec, &proof));
+            assert!(!verify_part_dec(&bls_pks[i], &gamma_g2, &(part_dec + G2::generator()), &proof));
+
+            parts.push(PartialDecryption { bls_pk: bls_pks[i], gamma_g2, part_dec });
+        }
+        assert!(batch_part_verify(&parts));
+        assert!(batch_part_verify::<E>(&[]));
+
+        parts[3].part_dec = sk[2].partial_decryption(parts[3].gamma_g2);
+        assert!(!batch_part_verify(&parts));
+    }
+
+    #[tokio::test]
+    async fn test_blinded_part_dec() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let t: usize = 2;
+        let TestCommittee { params, sk, pk, agg_key, .. } = test_committee::<E>(n, &mut rng).await;
+        let ct = encrypt::<E>(&agg_key, t, &params).unwrap();
+
+        let requester = RequesterKey::<E>::new(&mut rng);
+        let other = RequesterKey::<E>::new(&mut rng);
+
+        // the dummy party's partial decryption is gamma_g2 itself, the requester adds it
+        let mut partial_decryptions = vec![ct.gamma_g2];
+        for i in 1..n {
+            let (blinded, proof) = sk[i].blinded_partial_decryption(ct.gamma_g2, requester.pk);
+            assert!(verify_blinded_part_dec(&pk[i].bls_pk, &ct.gamma_g2, &requester.pk, &blinded, &proof));
+            assert!(!verify_blinded_part_dec(&pk[i].bls_pk, &ct.gamma_g2, &other.pk, &blinded, &proof));
+            assert!(!verify_blinded_part_dec(&pk[i % (n - 1) + 1].bls_pk, &ct.gamma_g2, &requester.pk, &blinded, &proof));
+
+            assert!(blinded.c2 != sk[i].partial_decryption(ct.gamma_g2));
+            assert!(other.unblind(&blinded) != sk[i].partial_decryption(ct.gamma_g2));
+            partial_decryptions.push(requester.unblind(&blinded));
+        }
+
+        let selector = [true, true, true, false];
+        let dec_key = agg_dec(&partial_decryptions, &ct.sa1, &ct.sa2, t, n, &selector, &agg_key, &params).await.unwrap();
+        assert!(dec_key == ct.enc_key);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_parts() {
+        let mut rng = ark_std::test_rng();
+        let n = 16;
+        let params = KZG10::<E, UniPoly381>::setup(n, &mut rng).unwrap();
+
+        let lagrange_polys: Vec<DensePolynomial<<E as Pairing>::ScalarField>> = (0..n)
+            .map(|j| lagrange_poly(n, j))
+            .collect();
+
+        let mut sk: Vec<SecretKey<E>> = Vec::new();
+        let mut pk: Vec<PublicKey<E>> = Vec::new();
+        for i in 0..n {
+            sk.push(SecretKey::<E>::new(&mut rng));
+            pk.push(sk[i].get_pk(i, &params, n, &lagrange_polys).await.unwrap())
+        }
+
+        let gamma_g2 = G2::rand(&mut rng);
+        let mut parts: HashMap<usize, G2> = (0..n).map(|i| (i, sk[i].partial_decryption(gamma_g2))).collect();
+        assert!(invalid_parts(&gamma_g2, &parts, &pk).is_empty());
+
+        parts.insert(3, sk[4].partial_decryption(gamma_g2));
+        parts.insert(11, G2::zero());
+        parts.insert(12, parts[&12] + G2::generator());
+        parts.insert(n, sk[0].partial_decryption(gamma_g2));
+        assert_eq!(invalid_parts(&gamma_g2, &parts, &pk), vec![3, 11, 12, n]);
+    }
+
+    #[tokio::test]
+    async fn test_decryption_cca() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t: usize = 3;
+
+        let TestCommittee { params, sk, agg_key, .. } = test_committee::<E>(n, &mut rng).await;
+        let (ct, proof) = encrypt_cca::<E>(&agg_key, t, &params).unwrap();
+
+        let mut partial_decryptions: Vec<G2> = vec![G2::zero(); n];
+        let mut selector: Vec<bool> = vec![false; n];
+        for i in 0..t + 1 {
+            partial_decryptions[i] = sk[i].partial_decryption(ct.gamma_g2);
+            selector[i] = true;
+        }
+
+        let dec_key = agg_dec_cca(&partial_decryptions, &ct.gamma_g2, &ct.sa1, &ct.sa2, &proof, ct.t, n, &selector, &agg_key, &params).await;
+        assert!(dec_key == Ok(ct.enc_key));
+
+        // a mauled ciphertext is rejected before the partial decryptions are used
+        let mut sa2 = ct.sa2;
+        sa2[3] += G2::generator();
+        let dec_key = agg_dec_cca(&partial_decryptions, &ct.gamma_g2, &ct.sa1, &sa2, &proof, ct.t, n, &selector, &agg_key, &params).await;
+        assert!(dec_key == Err(Error::InvalidProof));
+    }
+
+    fn read_file<T: CanonicalDeserialize>(path: &str) -> T {
+        let contents = std::fs::read(path).unwrap();
+        T::deserialize_compressed(contents.as_slice()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_is_valid() {
+        let mut rng = ark_std::test_rng();
+        let n = 2;
+
+        let params: UniversalParams<E> = read_file("transcript-512");
+        let lagrange_helper: LagrangePolyHelper = read_file("lagrangehelpers/2");
+        let is_valid_helper: IsValidHelper = read_file("isvalidhelpers/2");
+
+        let sk = SecretKey::<E>::new(&mut rng);
+        let mut pk = get_pk_exp(&sk, 1, n, &lagrange_helper).unwrap();
+        assert!(is_valid(&pk, n, &params, &is_valid_helper).await);
+
+        // hints of another party
+        pk.id = 0;
+        assert!(!is_valid(&pk, n, &params, &is_valid_helper).await);
+
+        // out of range id
+        pk.id = n;
+        assert!(!is_valid(&pk, n, &params, &is_valid_helper).await);
+
+        pk.id = 1;
+        pk.sk_li += G1::generator();
+        assert!(!is_valid(&pk, n, &params, &is_valid_helper).await);
+
+        let mut sk_zero = SecretKey::<E>::new(&mut rng);
+        sk_zero.nullify();
+        let pks = vec![get_pk_exp(&sk_zero, 0, n, &lagrange_helper).unwrap(), pk];
+        assert_eq!(invalid_pks(&pks, n, &params, &is_valid_helper).await, vec![1]);
+
+        // keys in the wrong slots
+        let pks = vec![get_pk_exp(&sk, 1, n, &lagrange_helper).unwrap(), get_pk_exp(&sk_zero, 0, n, &lagrange_helper).unwrap()];
+        assert_eq!(invalid_pks(&pks, n, &params, &is_valid_helper).await, vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_helpers_bn254() {
+        type E = ark_bn254::Bn254;
+
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let t = 2;
+        let TestCommittee { params, sk, pk, agg_key, .. } = test_committee::<E>(n, &mut rng).await;
+
+        let is_valid_helper = IsValidHelper::<E>::new(n, &params).await.unwrap();
+        assert!(invalid_pks(&pk, n, &params, &is_valid_helper).await.is_empty());
+
+        let ct = encrypt::<E>(&agg_key, t, &params).unwrap();
+
+        let g1 = params.powers_of_g[0].into();
+        let mut partial_decryptions = Vec::new();
+        let mut selector = Vec::new();
+        for i in 0..n {
+            let part_dec = sk[i].partial_decryption(ct.gamma_g2);
+            assert!(part_verify(ct.gamma_g2, &pk[i], g1, part_dec));
+            partial_decryptions.push(if i <= t { part_dec } else { <E as Pairing>::G2::zero() });
+            selector.push(i <= t);
+        }
+
+        let dec_key = agg_dec(&partial_decryptions, &ct.sa1, &ct.sa2, ct.t, n, &selector, &agg_key, &params).await.unwrap();
+        assert!(dec_key == ct.enc_key);
     }
 }
@@ -0,0 +1,139 @@
+use aes::Aes256;
+use aes_gcm::{aead::{Aead, KeyInit, Payload}, Aes256Gcm};
+use ark_ec::pairing::{Pairing, PairingOutput};
+use block_modes::{block_padding::Pkcs7, BlockMode, Cbc};
+use chacha20poly1305::ChaCha20Poly1305;
+use rand::RngCore;
+
+use crate::{encryption::{derive_key, derive_key_legacy, KDF_VERSION}, error::Error};
+
+type Aes256Cbc = Cbc<Aes256, Pkcs7>;
+
+/// Data encapsulation mode used to encrypt the message under the key derived from `Ciphertext::enc_key`.
+/// The AEAD modes bind the ciphertext header as associated data, the CBC ones are unauthenticated.
+/// `Aes256Cbc` is only kept to decrypt the ciphertexts of legacy clients, with the legacy KDF. It keeps
+/// id 0, the value of a `dem` field those clients never set, while `/encrypt` gives them
+/// `Aes256CbcHkdf`, the same mode with the KDF of the others.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dem {
+    Aes256Cbc = 0,
+    Aes256Gcm = 1,
+    ChaCha20Poly1305 = 2,
+    Aes256CbcHkdf = 3,
+}
+
+impl Dem {
+    pub fn from_u32(dem: u32) -> Result<Self, Error> {
+        match dem {
+            0 => Ok(Dem::Aes256Cbc),
+            1 => Ok(Dem::Aes256Gcm),
+            2 => Ok(Dem::ChaCha20Poly1305),
+            3 => Ok(Dem::Aes256CbcHkdf),
+            _ => Err(Error::UnknownDem(dem)),
+        }
+    }
+
+    /// The dem to encrypt with for a request's `dem`: the legacy `Aes256Cbc` is only kept for
+    /// decryption, so id 0, what a request that doesn't set it has, stands for `Aes256CbcHkdf`
+    pub fn for_encryption(dem: u32) -> Result<Self, Error> {
+        match Dem::from_u32(dem)? {
+            Dem::Aes256Cbc => Ok(Dem::Aes256CbcHkdf),
+            dem => Ok(dem),
+        }
+    }
+
+    pub fn is_authenticated(&self) -> bool {
+        matches!(self, Dem::Aes256Gcm | Dem::ChaCha20Poly1305)
+    }
+
+    /// Version of the KDF of the dem, 0 for the legacy one of `Aes256Cbc` and `KDF_VERSION` for the
+    /// others. The id of a dem fixes its KDF, a new version means new ids.
+    pub fn kdf_version(&self) -> u32 {
+        match self {
+            Dem::Aes256Cbc => 0,
+            _ => KDF_VERSION,
+        }
+    }
+
+    /// Symmetric key for `enc_key` with the KDF of `kdf_version`
+    pub fn derive_key<E: Pairing>(&self, enc_key: &PairingOutput<E>) -> [u8; 32] {
+        match self.kdf_version() {
+            0 => derive_key_legacy(enc_key),
+            _ => derive_key(enc_key),
+        }
+    }
+
+    /// length of the nonce, or of the iv for the CBC modes
+    pub fn nonce_len(&self) -> usize {
+        match self {
+            Dem::Aes256Gcm | Dem::ChaCha20Poly1305 => 12,
+            Dem::Aes256Cbc | Dem::Aes256CbcHkdf => 16,
+        }
+    }
+
+    /// Encrypts `msg` with a fresh nonce, returns the nonce and the encrypted message
+    pub fn encrypt<R: RngCore>(&self, key: &[u8; 32], msg: &[u8], ad: &[u8], rng: &mut R) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let mut nonce = vec![0u8; self.nonce_len()];
+        rng.fill_bytes(&mut nonce);
+
+        let payload = Payload { msg, aad: ad };
+        let enc = match self {
+            Dem::Aes256Gcm => Aes256Gcm::new(key.into()).encrypt(nonce.as_slice().into(), payload).map_err(|_| Error::Dem)?,
+            Dem::ChaCha20Poly1305 => ChaCha20Poly1305::new(key.into()).encrypt(nonce.as_slice().into(), payload).map_err(|_| Error::Dem)?,
+            Dem::Aes256Cbc | Dem::Aes256CbcHkdf => Aes256Cbc::new_from_slices(key, &nonce).map_err(|_| Error::Dem)?.encrypt_vec(msg),
+        };
+
+        Ok((nonce, enc))
+    }
+
+    /// Decrypts `enc`, returns `Error::Dem` if the nonce has the wrong length or if the AEAD tag doesn't
+    /// match `enc` and `ad`
+    pub fn decrypt(&self, key: &[u8; 32], nonce: &[u8], enc: &[u8], ad: &[u8]) -> Result<Vec<u8>, Error> {
+        if nonce.len() != self.nonce_len() {
+            return Err(Error::Dem);
+        }
+
+        let payload = Payload { msg: enc, aad: ad };
+        match self {
+            Dem::Aes256Gcm => Aes256Gcm::new(key.into()).decrypt(nonce.into(), payload).map_err(|_| Error::Dem),
+            Dem::ChaCha20Poly1305 => ChaCha20Poly1305::new(key.into()).decrypt(nonce.into(), payload).map_err(|_| Error::Dem),
+            Dem::Aes256Cbc | Dem::Aes256CbcHkdf => Aes256Cbc::new_from_slices(key, nonce).map_err(|_| Error::Dem)?.decrypt_vec(enc).map_err(|_| Error::Dem),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dem() {
+        let mut rng = ark_std::test_rng();
+        let mut key = [0u8; 32];
+        rng.fill_bytes(&mut key);
+
+        let msg = b"Hello, world!";
+        let ad = b"header";
+
+        // a request that doesn't set `dem` is decrypted with the legacy mode, but encrypted with its
+        // successor
+        assert_eq!(Dem::from_u32(0), Ok(Dem::Aes256Cbc));
+        assert_eq!(Dem::for_encryption(0), Ok(Dem::Aes256CbcHkdf));
+        assert_eq!(Dem::for_encryption(1), Ok(Dem::Aes256Gcm));
+        assert_eq!(Dem::from_u32(4), Err(Error::UnknownDem(4)));
+        assert_eq!((Dem::Aes256Cbc.kdf_version(), Dem::Aes256CbcHkdf.kdf_version()), (0, KDF_VERSION));
+
+        for dem in [Dem::Aes256Gcm, Dem::ChaCha20Poly1305, Dem::Aes256Cbc, Dem::Aes256CbcHkdf] {
+            let (nonce, mut enc) = dem.encrypt(&key, msg, ad, &mut rng).unwrap();
+            assert_eq!(nonce.len(), dem.nonce_len());
+            assert_eq!(dem.decrypt(&key, &nonce, &enc, ad).unwrap(), msg);
+
+            if dem.is_authenticated() {
+                assert_eq!(dem.decrypt(&key, &nonce, &enc, b"another header"), Err(Error::Dem));
+
+                enc[0] ^= 1;
+                assert_eq!(dem.decrypt(&key, &nonce, &enc, ad), Err(Error::Dem));
+            }
+        }
+    }
+}
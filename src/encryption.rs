@@ -1,13 +1,20 @@
 use std::ops::Mul;
 
-use crate::{kzg::UniversalParams, setup::AggregateKey};
+use crate::{curve::CurveId, error::Error, kzg::{check_degree_is_too_large, UniversalParams}, setup::AggregateKey};
 use ark_ec::{
     pairing::{Pairing, PairingOutput},
     Group,
 };
+use ark_ff::PrimeField;
 use ark_serialize::*;
 use ark_std::{UniformRand, Zero};
+use hkdf::Hkdf;
 use rand::rngs::OsRng;
+use sha2::{Digest, Sha256, Sha512};
+
+/// Version of the HKDF derivation of `derive_key`, part of its info label. Version 0 is the legacy
+/// `derive_key_legacy`, see `Dem::kdf_version` for the version of each dem.
+pub const KDF_VERSION: u32 = 1;
 
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
 pub struct Ciphertext<E: Pairing> {
@@ -34,6 +41,92 @@ impl<E: Pairing> Ciphertext<E> {
             t,
         }
     }
+
+    /// serialization of the public part of the ciphertext, bound as associated data by the AEAD modes
+    pub fn header(&self) -> Vec<u8> {
+        header::<E>(&self.gamma_g2, &self.sa1, &self.sa2, self.t)
+    }
+}
+
+/// Serializes `gamma_g2`, `sa1`, `sa2` and `t` of a ciphertext
+pub fn header<E: Pairing>(gamma_g2: &E::G2, sa1: &[E::G1; 2], sa2: &[E::G2; 6], t: usize) -> Vec<u8> {
+    let mut header = Vec::new();
+    gamma_g2.serialize_compressed(&mut header).unwrap();
+    sa1.serialize_compressed(&mut header).unwrap();
+    sa2.serialize_compressed(&mut header).unwrap();
+    (t as u64).serialize_compressed(&mut header).unwrap();
+
+    header
+}
+
+/// Derives the symmetric key from the compressed serialization of `enc_key` with HKDF-SHA256 and the
+/// info label `silent-threshold/kdf/v{KDF_VERSION}/enc_key`, so that it doesn't depend on how arkworks
+/// formats a `PairingOutput`. Ciphertexts don't record the KDF, the dem does (see `Dem::kdf_version`):
+/// a new version comes with new dem ids.
+pub fn derive_key<E: Pairing>(enc_key: &PairingOutput<E>) -> [u8; 32] {
+    let mut ikm = Vec::new();
+    enc_key.serialize_compressed(&mut ikm).unwrap();
+
+    let info = format!("silent-threshold/kdf/v{}/enc_key", KDF_VERSION);
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(None, &ikm)
+        .expand(info.as_bytes(), &mut key)
+        .unwrap();
+
+    key
+}
+
+/// SHA-256 of the `Display` output of `enc_key`, KDF version 0, only kept to decrypt the
+/// `Dem::Aes256Cbc` ciphertexts of older versions
+pub fn derive_key_legacy<E: Pairing>(enc_key: &PairingOutput<E>) -> [u8; 32] {
+    Sha256::digest(enc_key.to_string().as_bytes()).into()
+}
+
+/// Everything `encrypt` needs to encrypt to one committee with one threshold: the preprocessed
+/// values of its `AggregateKey` and the few powers of the transcript the header is made of. Its size
+/// doesn't depend on `n`, so clients can fetch it once and encrypt without the keys or the transcript.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EncryptionKey<E: Pairing> {
+    /// number of parties, the dummy one included
+    pub n: usize,
+    /// threshold of the ciphertexts, not raised by the padding
+    pub t: usize,
+    pub ask: E::G1,
+    pub z_g2: E::G2,
+    pub g: E::G1Affine,
+    /// g^{tau^t} for `t` raised by the padding
+    pub g_tau_t: E::G1Affine,
+    pub h: E::G2Affine,
+    pub h_tau: E::G2Affine,
+}
+
+impl<E: Pairing> EncryptionKey<E> {
+    /// The key of `apk` for threshold `t`, `apk` needn't have hints
+    pub fn new(apk: &AggregateKey<E>, t: usize, params: &UniversalParams<E>) -> Result<Self, Error> {
+        if t >= apk.n {
+            return Err(Error::Threshold { t, n: apk.n });
+        }
+        // the padding parties of apk always sign, so they count on top of t
+        check_degree_is_too_large(t + apk.padding(), params.powers_of_g.len())?;
+        check_degree_is_too_large(1, params.powers_of_h.len())?;
+
+        Ok(EncryptionKey {
+            n: apk.n,
+            t,
+            ask: apk.ask,
+            z_g2: apk.z_g2,
+            g: params.powers_of_g[0],
+            g_tau_t: params.powers_of_g[t + apk.padding()],
+            h: params.powers_of_h[0],
+            h_tau: params.powers_of_h[1],
+        })
+    }
+
+    /// e(g, h), the `e_gh` of the aggregate key, computed rather than sent along since it's 576 bytes
+    /// on BLS12-381
+    pub fn e_gh(&self) -> PairingOutput<E> {
+        E::pairing(self.g, self.h)
+    }
 }
 
 /// t is the threshold for encryption and apk is the aggregated public key
@@ -41,27 +134,81 @@ pub fn encrypt<E: Pairing>(
     apk: &AggregateKey<E>,
     t: usize,
     params: &UniversalParams<E>,
-) -> Ciphertext<E> {
-    let mut rng = OsRng;
-    let gamma = E::ScalarField::rand(&mut rng);
-    let gamma_g2 = params.powers_of_h[0] * gamma;
+) -> Result<Ciphertext<E>, Error> {
+    Ok(encrypt_with_key(&EncryptionKey::new(apk, t, params)?))
+}
 
-    let g = params.powers_of_g[0];
-    let h = params.powers_of_h[0];
+/// `encrypt` with the threshold of `ek`
+pub fn encrypt_with_key<E: Pairing>(ek: &EncryptionKey<E>) -> Ciphertext<E> {
+    let gamma = E::ScalarField::rand(&mut OsRng);
+    encrypt_with_gamma_g2(ek, ek.h * gamma)
+}
 
-    // todo: avoid benchmarking this
-    // let e_gh = E::pairing(g, h);
+const TAG_DOMAIN: &[u8] = b"silent-threshold/tag/v1";
 
-    let mut sa1 = [E::G1::generator(); 2];
-    let mut sa2 = [E::G2::generator(); 6];
+/// `gamma_g2` of the ciphertexts for `tag`
+pub fn tag_gamma_g2<E: CurveId>(tag: &[u8]) -> E::G2 {
+    E::hash_to_g2(TAG_DOMAIN, tag)
+}
 
+/// `encrypt` with `gamma_g2 = tag_gamma_g2(tag)` in place of a random one, for a tag the caller picks
+/// (block height, epoch, auction id). One partial decryption of the tag per party then decrypts every
+/// ciphertext for it, see `decryption::agg_dec_batch`, so parties should only issue them once all of
+/// these may be opened. Nobody knows the discrete log of `gamma_g2`, so there is no `encrypt_cca`
+/// counterpart.
+pub fn encrypt_for_tag<E: CurveId>(
+    apk: &AggregateKey<E>,
+    t: usize,
+    params: &UniversalParams<E>,
+    tag: &[u8],
+) -> Result<Ciphertext<E>, Error> {
+    Ok(encrypt_for_tag_with_key(&EncryptionKey::new(apk, t, params)?, tag))
+}
+
+/// `encrypt_for_tag` with the threshold of `ek`
+pub fn encrypt_for_tag_with_key<E: CurveId>(ek: &EncryptionKey<E>, tag: &[u8]) -> Ciphertext<E> {
+    encrypt_with_gamma_g2(ek, tag_gamma_g2::<E>(tag))
+}
+
+/// `encrypt_with_key` for a `gamma_g2` of the caller, typically a point hashed with
+/// `CurveId::hash_to_g2` under its own DST. `SecretKey::partial_decryption` of that point then
+/// decrypts the ciphertext, and every other one for the same point.
+pub fn encrypt_with_gamma_g2<E: Pairing>(ek: &EncryptionKey<E>, gamma_g2: E::G2) -> Ciphertext<E> {
+    let mut rng = OsRng;
     let mut s: [E::ScalarField; 5] = [E::ScalarField::zero(); 5];
 
     s.iter_mut()
         .for_each(|s| *s = E::ScalarField::rand(&mut rng));
 
+    let (sa1, sa2) = kem_header(ek, gamma_g2, &s);
+
+    // enc_key = s4*e_gh
+    let enc_key = ek.e_gh().mul(s[4]);
+
+    Ciphertext {
+        gamma_g2,
+        sa1,
+        sa2,
+        enc_key,
+        t: ek.t,
+    }
+}
+
+/// sa1 and sa2 for the randomness `s`, also used with the nonces and responses of `CiphertextProof`
+/// since every element is linear in `s`
+pub(crate) fn kem_header<E: Pairing>(
+    ek: &EncryptionKey<E>,
+    gamma_g2: E::G2,
+    s: &[E::ScalarField; 5],
+) -> ([E::G1; 2], [E::G2; 6]) {
+    let g = ek.g;
+    let h = ek.h;
+
+    let mut sa1 = [E::G1::generator(); 2];
+    let mut sa2 = [E::G2::generator(); 6];
+
     // sa1[0] = s0*ask + s3*g^{tau^t} + s4*g
-    sa1[0] = (apk.ask * s[0]) + (params.powers_of_g[t] * s[3]) + (params.powers_of_g[0] * s[4]);
+    sa1[0] = (ek.ask * s[0]) + (ek.g_tau_t * s[3]) + (g * s[4]);
 
     // sa1[1] = s2*g
     sa1[1] = g * s[2];
@@ -70,10 +217,10 @@ pub fn encrypt<E: Pairing>(
     sa2[0] = (h * s[0]) + (gamma_g2 * s[2]);
 
     // sa2[1] = s0*z_g2
-    sa2[1] = apk.z_g2 * s[0];
+    sa2[1] = ek.z_g2 * s[0];
 
     // sa2[2] = s0*h^tau + s1*h^tau
-    sa2[2] = params.powers_of_h[1] * (s[0] + s[1]);
+    sa2[2] = ek.h_tau * (s[0] + s[1]);
 
     // sa2[3] = s1*h
     sa2[3] = h * s[1];
@@ -82,26 +229,139 @@ pub fn encrypt<E: Pairing>(
     sa2[4] = h * s[3];
 
     // sa2[5] = s4*h^{tau - omega^0}
-    sa2[5] = (params.powers_of_h[1] + apk.h_minus1) * s[4];
+    sa2[5] = (E::G2::from(ek.h_tau) - h) * s[4];
 
-    // enc_key = s4*e_gh
-    let enc_key = apk.e_gh.mul(s[4]);
+    (sa1, sa2)
+}
 
-    Ciphertext {
-        gamma_g2,
-        sa1,
-        sa2,
-        enc_key,
-        t,
+/// Non-interactive proof of knowledge of `gamma` and `s0..s4` behind `gamma_g2`, `sa1` and `sa2`,
+/// made non-interactive with Fiat-Shamir over the whole header. Since the challenge depends on every
+/// element of the header, a ciphertext can't be mauled into another valid one, and decryptors can refuse
+/// to issue partial decryptions for a `gamma_g2` whose discrete log isn't known to the sender.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CiphertextProof<E: Pairing> {
+    pub c: E::ScalarField,
+    /// responses for gamma and s0..s4
+    pub z: [E::ScalarField; 6],
+}
+
+const CCA_DOMAIN: &[u8] = b"silent-threshold/cca/v1";
+
+fn cca_challenge<E: Pairing>(
+    ek: &EncryptionKey<E>,
+    header: &[u8],
+    r_gamma_g2: &E::G2,
+    r_sa1: &[E::G1; 2],
+    r_sa2: &[E::G2; 6],
+) -> E::ScalarField {
+    let mut bytes = Vec::new();
+    (ek.ask, ek.z_g2, ek.t as u64).serialize_compressed(&mut bytes).unwrap();
+    r_gamma_g2.serialize_compressed(&mut bytes).unwrap();
+    r_sa1.serialize_compressed(&mut bytes).unwrap();
+    r_sa2.serialize_compressed(&mut bytes).unwrap();
+
+    let digest = Sha512::new()
+        .chain_update(CCA_DOMAIN)
+        .chain_update(header)
+        .chain_update(&bytes)
+        .finalize();
+
+    E::ScalarField::from_le_bytes_mod_order(&digest)
+}
+
+/// Same as `encrypt` plus a `CiphertextProof` that `verify_ciphertext` checks before any
+/// partial decryption is requested for the ciphertext
+pub fn encrypt_cca<E: Pairing>(
+    apk: &AggregateKey<E>,
+    t: usize,
+    params: &UniversalParams<E>,
+) -> Result<(Ciphertext<E>, CiphertextProof<E>), Error> {
+    Ok(encrypt_cca_with_key(&EncryptionKey::new(apk, t, params)?))
+}
+
+/// `encrypt_cca` with the threshold of `ek`
+pub fn encrypt_cca_with_key<E: Pairing>(ek: &EncryptionKey<E>) -> (Ciphertext<E>, CiphertextProof<E>) {
+    let mut rng = OsRng;
+    let gamma = E::ScalarField::rand(&mut rng);
+    let gamma_g2 = ek.h * gamma;
+
+    let mut s: [E::ScalarField; 5] = [E::ScalarField::zero(); 5];
+    s.iter_mut()
+        .for_each(|s| *s = E::ScalarField::rand(&mut rng));
+
+    let (sa1, sa2) = kem_header(ek, gamma_g2, &s);
+    let enc_key = ek.e_gh().mul(s[4]);
+    let ct = Ciphertext::new(gamma_g2, sa1, sa2, enc_key, ek.t);
+
+    // commit with fresh nonces, the gamma_g2 in sa2[0] stays the one of the statement
+    let r_gamma = E::ScalarField::rand(&mut rng);
+    let mut r: [E::ScalarField; 5] = [E::ScalarField::zero(); 5];
+    r.iter_mut()
+        .for_each(|r| *r = E::ScalarField::rand(&mut rng));
+
+    let r_gamma_g2 = ek.h * r_gamma;
+    let (r_sa1, r_sa2) = kem_header(ek, gamma_g2, &r);
+
+    let c = cca_challenge(ek, &ct.header(), &r_gamma_g2, &r_sa1, &r_sa2);
+
+    let mut z = [r_gamma + c * gamma; 6];
+    for i in 0..5 {
+        z[i + 1] = r[i] + c * s[i];
+    }
+
+    (ct, CiphertextProof { c, z })
+}
+
+/// Checks the `CiphertextProof` of a ciphertext from `encrypt_cca` against the aggregate key it was
+/// encrypted to
+pub fn verify_ciphertext<E: Pairing>(
+    gamma_g2: &E::G2,
+    sa1: &[E::G1; 2],
+    sa2: &[E::G2; 6],
+    t: usize,
+    proof: &CiphertextProof<E>,
+    apk: &AggregateKey<E>,
+    params: &UniversalParams<E>,
+) -> bool {
+    match EncryptionKey::new(apk, t, params) {
+        Ok(ek) => verify_ciphertext_with_key(gamma_g2, sa1, sa2, proof, &ek),
+        Err(_) => false,
     }
 }
 
+/// `verify_ciphertext` for a ciphertext with the threshold of `ek`
+pub fn verify_ciphertext_with_key<E: Pairing>(
+    gamma_g2: &E::G2,
+    sa1: &[E::G1; 2],
+    sa2: &[E::G2; 6],
+    proof: &CiphertextProof<E>,
+    ek: &EncryptionKey<E>,
+) -> bool {
+    if gamma_g2.is_zero() {
+        return false;
+    }
+
+    // recompute the commitments as z*base - c*statement
+    let z_s: [E::ScalarField; 5] = [proof.z[1], proof.z[2], proof.z[3], proof.z[4], proof.z[5]];
+    let r_gamma_g2 = ek.h * proof.z[0] - *gamma_g2 * proof.c;
+    let (mut r_sa1, mut r_sa2) = kem_header(ek, *gamma_g2, &z_s);
+    for i in 0..2 {
+        r_sa1[i] -= sa1[i] * proof.c;
+    }
+    for i in 0..6 {
+        r_sa2[i] -= sa2[i] * proof.c;
+    }
+
+    let header = header::<E>(gamma_g2, sa1, sa2, ek.t);
+    cca_challenge(ek, &header, &r_gamma_g2, &r_sa1, &r_sa2) == proof.c
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        kzg::KZG10,
-        setup::{PublicKey, SecretKey}, utils::lagrange_poly,
+        decryption::agg_dec, kzg::KZG10,
+        setup::{PublicKey, SecretKey}, utils::{lagrange_poly, test_committee, TestCommittee},
     };
     use ark_ec::bls12::Bls12;
     use ark_poly::univariate::DensePolynomial;
@@ -111,8 +371,8 @@ mod tests {
     type G2 = <E as Pairing>::G2;
     type UniPoly381 = DensePolynomial<<E as Pairing>::ScalarField>;
 
-    #[test]
-    fn test_encryption() {
+    #[tokio::test]
+    async fn test_encryption() {
         let mut rng = ark_std::test_rng();
         let n = 8;
         let params = KZG10::<E, UniPoly381>::setup(n, &mut rng).unwrap();
@@ -126,11 +386,11 @@ mod tests {
 
         for i in 0..n {
             sk.push(SecretKey::<E>::new(&mut rng));
-            pk.push(sk[i].get_pk(0, &params, n, &lagrange_polys).await)
+            pk.push(sk[i].get_pk(0, &params, n, &lagrange_polys).await.unwrap())
         }
 
-        let ak = AggregateKey::<E>::new(pk, n, &params);
-        let ct = encrypt::<E>(&ak, 2, &params);
+        let ak = AggregateKey::<E>::new(pk, n, &params).unwrap();
+        let ct = encrypt::<E>(&ak, 2, &params).unwrap();
 
         let mut ct_bytes = Vec::new();
         ct.serialize_compressed(&mut ct_bytes).unwrap();
@@ -151,4 +411,69 @@ mod tests {
         println!("G2 len: {} bytes", g2_bytes.len());
         println!("GT len: {} bytes", e_gh_bytes.len());
     }
+
+    #[tokio::test]
+    async fn test_encryption_cca() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let TestCommittee { params, agg_key: ak, .. } = test_committee::<E>(n, &mut rng).await;
+        let (ct, proof) = encrypt_cca::<E>(&ak, 2, &params).unwrap();
+        assert!(verify_ciphertext(&ct.gamma_g2, &ct.sa1, &ct.sa2, ct.t, &proof, &ak, &params));
+
+        // any change to the header invalidates the proof
+        assert!(!verify_ciphertext(&ct.gamma_g2, &ct.sa1, &ct.sa2, 3, &proof, &ak, &params));
+        assert!(!verify_ciphertext(&(ct.gamma_g2 + G2::generator()), &ct.sa1, &ct.sa2, ct.t, &proof, &ak, &params));
+        for i in 0..2 {
+            let mut sa1 = ct.sa1;
+            sa1[i] += G1::generator();
+            assert!(!verify_ciphertext(&ct.gamma_g2, &sa1, &ct.sa2, ct.t, &proof, &ak, &params));
+        }
+        for i in 0..6 {
+            let mut sa2 = ct.sa2;
+            sa2[i] += G2::generator();
+            assert!(!verify_ciphertext(&ct.gamma_g2, &ct.sa1, &sa2, ct.t, &proof, &ak, &params));
+        }
+
+        // a plain ciphertext reusing the proof, e.g. to get partial decryptions of another gamma_g2
+        let other = encrypt::<E>(&ak, 2, &params).unwrap();
+        assert!(!verify_ciphertext(&other.gamma_g2, &other.sa1, &other.sa2, other.t, &proof, &ak, &params));
+    }
+
+    #[tokio::test]
+    async fn test_encryption_key() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let TestCommittee { params, sk, agg_key: ak, .. } = test_committee::<E>(n, &mut rng).await;
+        assert!(EncryptionKey::new(&ak, n, &params).err() == Some(Error::Threshold { t: n, n }));
+
+        // what a client would download
+        let mut ek_bytes = Vec::new();
+        EncryptionKey::new(&ak.without_hints(), 2, &params).unwrap().serialize_compressed(&mut ek_bytes).unwrap();
+        println!("Compressed encryption key: {} bytes", ek_bytes.len());
+        let ek = EncryptionKey::<E>::deserialize_compressed(&ek_bytes[..]).unwrap();
+        assert!(ek.e_gh() == ak.e_gh);
+
+        let ct = encrypt_with_key(&ek);
+        let parts: Vec<G2> = sk.iter().map(|sk| sk.partial_decryption(ct.gamma_g2)).collect();
+        let selector = [true, true, true, false, false, false, false, false];
+        let dec_key = agg_dec(&parts, &ct.sa1, &ct.sa2, ct.t, n, &selector, &ak, &params).await;
+        assert!(dec_key == Ok(ct.enc_key));
+
+        let (ct, proof) = encrypt_cca_with_key(&ek);
+        assert!(verify_ciphertext(&ct.gamma_g2, &ct.sa1, &ct.sa2, ct.t, &proof, &ak, &params));
+        assert!(verify_ciphertext_with_key(&ct.gamma_g2, &ct.sa1, &ct.sa2, &proof, &ek));
+    }
+
+    #[test]
+    fn test_derive_key() {
+        let e_gh = E::pairing(G1::generator(), G2::generator());
+
+        // pinned so that Rust and Go clients, and future arkworks versions, derive the same key
+        assert_eq!(
+            hex::encode(derive_key(&e_gh)),
+            "3c5a398c2c5531a40e14cb5d13808aeb04c2f381d2f8f9b1b2672e4fed8ac4cd"
+        );
+        assert_ne!(derive_key(&e_gh), derive_key(&(e_gh + e_gh)));
+        assert_ne!(derive_key(&e_gh), derive_key_legacy(&e_gh));
+    }
 }
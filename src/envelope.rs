@@ -0,0 +1,445 @@
+use ark_ec::pairing::PairingOutput;
+use ark_serialize::*;
+
+use rand::rngs::OsRng;
+
+use crate::{
+    curve::CurveId, dem::Dem, encryption::{encrypt_cca_with_key, encrypt_for_tag_with_key, encrypt_with_key, Ciphertext, CiphertextProof, EncryptionKey}, error::Error, timelock::{encrypt_timelock_with_key, Timelock}
+};
+
+pub const MAGIC: &[u8; 4] = b"STE\0";
+pub const VERSION: u8 = 1;
+
+/// set in `flags` when the envelope carries a `CiphertextProof`
+pub const FLAG_PROOF: u8 = 1;
+/// set in `flags` when the envelope was encrypted for a tag, see `encrypt_for_tag`
+pub const FLAG_TAG: u8 = 2;
+/// set in `flags` when the key is also locked to a beacon round, see `timelock::Timelock`
+pub const FLAG_TIMELOCK: u8 = 4;
+
+/// Self-contained ciphertext, laid out as (integers are big endian)
+///
+/// | field   | size                          |
+/// |---------|-------------------------------|
+/// | magic   | 4, `STE\0`                    |
+/// | version | 1                             |
+/// | curve   | 1, `CurveId::CURVE_ID`        |
+/// | flags   | 1, `FLAG_PROOF`, `FLAG_TAG`, `FLAG_TIMELOCK` |
+/// | dem     | 1, `Dem`                      |
+/// | n       | 4                             |
+/// | t       | 4                             |
+/// | header  | 4 + compressed `gamma_g2`, `sa1`, `sa2` |
+/// | proof   | 4 + compressed `CiphertextProof`, only with `FLAG_PROOF` |
+/// | tag     | 4 + tag, only with `FLAG_TAG` |
+/// | timelock | 4 + compressed `Timelock`, only with `FLAG_TIMELOCK` |
+/// | nonce   | 1 + nonce                     |
+/// | payload | 4 + encrypted message         |
+///
+/// The payload is encrypted with everything before the nonce as associated data, see `ad`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Envelope<E: CurveId> {
+    pub n: usize,
+    pub t: usize,
+    pub gamma_g2: E::G2,
+    pub sa1: [E::G1; 2],
+    pub sa2: [E::G2; 6],
+    pub proof: Option<CiphertextProof<E>>,
+    /// the tag `gamma_g2` was hashed from, the parties decrypt the tag rather than `gamma_g2`
+    pub tag: Option<Vec<u8>>,
+    /// the key locked to a beacon round, which opens the envelope without the committee
+    pub timelock: Option<Timelock<E>>,
+    pub dem: Dem,
+    pub nonce: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// The data doesn't start with `MAGIC`
+    BadMagic,
+    UnsupportedVersion(u8),
+    /// The envelope was produced on another curve
+    WrongCurve(u8),
+    UnknownDem(u8),
+    UnknownFlags(u8),
+    /// The nonce length doesn't match the dem
+    BadNonce,
+    /// The KEM header can't be deserialized, or its section is longer than the header
+    MalformedHeader,
+    /// The `CiphertextProof` can't be deserialized, or its section is longer than the proof
+    MalformedProof,
+    /// The `Timelock` can't be deserialized, or its section is longer than the lock
+    MalformedTimelock,
+    /// The data ended before the envelope did
+    Truncated,
+    /// There is data after the payload
+    TrailingData,
+}
+
+impl<E: CurveId> Envelope<E> {
+    pub fn new(ct: &Ciphertext<E>, n: usize, dem: Dem, nonce: Vec<u8>, payload: Vec<u8>) -> Self {
+        Envelope {
+            n,
+            t: ct.t,
+            gamma_g2: ct.gamma_g2,
+            sa1: ct.sa1,
+            sa2: ct.sa2,
+            proof: None,
+            tag: None,
+            timelock: None,
+            dem,
+            nonce,
+            payload,
+        }
+    }
+
+    /// Encrypts `msg` to the committee of `ek` with `dem`, the whole client side of the scheme. With
+    /// `cca` the envelope carries a `CiphertextProof`, see `encrypt_cca`.
+    pub fn seal(ek: &EncryptionKey<E>, dem: Dem, msg: &[u8], cca: bool) -> Result<Self, Error> {
+        let (ct, proof) = if cca {
+            let (ct, proof) = encrypt_cca_with_key(ek);
+            (ct, Some(proof))
+        } else {
+            (encrypt_with_key(ek), None)
+        };
+
+        let envelope = Envelope::new(&ct, ek.n, dem, Vec::new(), Vec::new());
+        let envelope = match proof {
+            Some(proof) => envelope.with_proof(proof),
+            None => envelope,
+        };
+        envelope.with_message(&ct.enc_key, msg)
+    }
+
+    /// `seal` for `tag`, see `encrypt_for_tag`
+    pub fn seal_for_tag(ek: &EncryptionKey<E>, dem: Dem, msg: &[u8], tag: &[u8]) -> Result<Self, Error> {
+        let ct = encrypt_for_tag_with_key(ek, tag);
+
+        Envelope::new(&ct, ek.n, dem, Vec::new(), Vec::new())
+            .with_tag(tag.to_vec())
+            .with_message(&ct.enc_key, msg)
+    }
+
+    /// `seal` whose key is also locked to `round` of the beacon with public key `beacon_pk`, see
+    /// `timelock::encrypt_timelock`
+    pub fn seal_timelock(ek: &EncryptionKey<E>, dem: Dem, msg: &[u8], beacon_pk: &E::G1, round: u64) -> Result<Self, Error> {
+        let (ct, timelock) = encrypt_timelock_with_key(ek, beacon_pk, round);
+
+        Envelope::new(&ct, ek.n, dem, Vec::new(), Vec::new())
+            .with_timelock(timelock)
+            .with_message(&ct.enc_key, msg)
+    }
+
+    /// attaches the proof of a ciphertext from `encrypt_cca`
+    pub fn with_proof(mut self, proof: CiphertextProof<E>) -> Self {
+        self.proof = Some(proof);
+        self
+    }
+
+    /// records the tag of a ciphertext from `encrypt_for_tag`
+    pub fn with_tag(mut self, tag: Vec<u8>) -> Self {
+        self.tag = Some(tag);
+        self
+    }
+
+    /// attaches the lock of a ciphertext from `encrypt_timelock`
+    pub fn with_timelock(mut self, timelock: Timelock<E>) -> Self {
+        self.timelock = Some(timelock);
+        self
+    }
+
+    /// encrypts `msg` into the payload with `enc_key`, the key of the ciphertext, once everything
+    /// else is set since it is all bound to the payload
+    pub fn with_message(mut self, enc_key: &PairingOutput<E>, msg: &[u8]) -> Result<Self, Error> {
+        let key = self.dem.derive_key(enc_key);
+        let (nonce, payload) = self.dem.encrypt(&key, msg, &self.ad(), &mut OsRng)?;
+        self.nonce = nonce;
+        self.payload = payload;
+        Ok(self)
+    }
+
+    /// associated data of the payload: the encoding up to the nonce, so that n, the dem, the flags
+    /// and every section are bound to it along with the header. The nonce is an input of the dem.
+    pub fn ad(&self) -> Vec<u8> {
+        let mut kem = Vec::new();
+        (self.gamma_g2, self.sa1, self.sa2).serialize_compressed(&mut kem).unwrap();
+
+        let mut flags = 0;
+        let mut proof = Vec::new();
+        if let Some(p) = &self.proof {
+            flags |= FLAG_PROOF;
+            p.serialize_compressed(&mut proof).unwrap();
+        }
+        if self.tag.is_some() {
+            flags |= FLAG_TAG;
+        }
+        let tag = self.tag.as_deref().unwrap_or_default();
+        let mut timelock = Vec::new();
+        if let Some(lock) = &self.timelock {
+            flags |= FLAG_TIMELOCK;
+            lock.serialize_compressed(&mut timelock).unwrap();
+        }
+
+        let mut out = Vec::with_capacity(4 + 4 + 4 + 4 + 4 + kem.len() + 4 + proof.len() + 4 + tag.len() + 4 + timelock.len());
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(E::CURVE_ID);
+        out.push(flags);
+        out.push(self.dem as u8);
+        out.extend_from_slice(&(self.n as u32).to_be_bytes());
+        out.extend_from_slice(&(self.t as u32).to_be_bytes());
+        out.extend_from_slice(&(kem.len() as u32).to_be_bytes());
+        out.extend_from_slice(&kem);
+        if flags & FLAG_PROOF != 0 {
+            out.extend_from_slice(&(proof.len() as u32).to_be_bytes());
+            out.extend_from_slice(&proof);
+        }
+        if flags & FLAG_TAG != 0 {
+            out.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+            out.extend_from_slice(tag);
+        }
+        if flags & FLAG_TIMELOCK != 0 {
+            out.extend_from_slice(&(timelock.len() as u32).to_be_bytes());
+            out.extend_from_slice(&timelock);
+        }
+
+        out
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = self.ad();
+        out.reserve(1 + self.nonce.len() + 4 + self.payload.len());
+        out.push(self.nonce.len() as u8);
+        out.extend_from_slice(&self.nonce);
+        out.extend_from_slice(&(self.payload.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.payload);
+
+        out
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, EnvelopeError> {
+        let mut r = Reader { bytes };
+
+        if r.take(4)? != MAGIC {
+            return Err(EnvelopeError::BadMagic);
+        }
+
+        let version = r.u8()?;
+        if version != VERSION {
+            return Err(EnvelopeError::UnsupportedVersion(version));
+        }
+
+        let curve = r.u8()?;
+        if curve != E::CURVE_ID {
+            return Err(EnvelopeError::WrongCurve(curve));
+        }
+
+        let flags = r.u8()?;
+        if flags & !(FLAG_PROOF | FLAG_TAG | FLAG_TIMELOCK) != 0 {
+            return Err(EnvelopeError::UnknownFlags(flags));
+        }
+
+        let dem_id = r.u8()?;
+        let dem = Dem::from_u32(dem_id as u32).map_err(|_| EnvelopeError::UnknownDem(dem_id))?;
+
+        let n = r.u32()? as usize;
+        let t = r.u32()? as usize;
+
+        let kem_len = r.u32()? as usize;
+        let (gamma_g2, sa1, sa2) = read_all::<(E::G2, [E::G1; 2], [E::G2; 6])>(r.take(kem_len)?, EnvelopeError::MalformedHeader)?;
+
+        let mut proof = None;
+        if flags & FLAG_PROOF != 0 {
+            let proof_len = r.u32()? as usize;
+            proof = Some(read_all::<CiphertextProof<E>>(r.take(proof_len)?, EnvelopeError::MalformedProof)?);
+        }
+
+        let mut tag = None;
+        if flags & FLAG_TAG != 0 {
+            let tag_len = r.u32()? as usize;
+            tag = Some(r.take(tag_len)?.to_vec());
+        }
+
+        let mut timelock = None;
+        if flags & FLAG_TIMELOCK != 0 {
+            let timelock_len = r.u32()? as usize;
+            timelock = Some(read_all::<Timelock<E>>(r.take(timelock_len)?, EnvelopeError::MalformedTimelock)?);
+        }
+
+        let nonce_len = r.u8()? as usize;
+        let nonce = r.take(nonce_len)?.to_vec();
+        if nonce.len() != dem.nonce_len() {
+            return Err(EnvelopeError::BadNonce);
+        }
+
+        let payload_len = r.u32()? as usize;
+        let payload = r.take(payload_len)?.to_vec();
+
+        if !r.bytes.is_empty() {
+            return Err(EnvelopeError::TrailingData);
+        }
+
+        Ok(Envelope {
+            n,
+            t,
+            gamma_g2,
+            sa1,
+            sa2,
+            proof,
+            tag,
+            timelock,
+            dem,
+            nonce,
+            payload,
+        })
+    }
+}
+
+/// Deserializes a length-prefixed section, `err` if it can't be or if bytes are left over: a section
+/// encodes exactly one value, so that an envelope has a single encoding
+fn read_all<T: CanonicalDeserialize>(mut bytes: &[u8], err: EnvelopeError) -> Result<T, EnvelopeError> {
+    match T::deserialize_compressed(&mut bytes) {
+        Ok(value) if bytes.is_empty() => Ok(value),
+        _ => Err(err),
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], EnvelopeError> {
+        if self.bytes.len() < len {
+            return Err(EnvelopeError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, EnvelopeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, EnvelopeError> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decryption::agg_dec, encryption::verify_ciphertext, timelock::Beacon, utils::{test_committee, TestCommittee}
+    };
+    use ark_ec::{pairing::Pairing, Group};
+    use ark_std::UniformRand;
+
+    type E = ark_bls12_381::Bls12_381;
+    type G1 = <E as Pairing>::G1;
+    type G2 = <E as Pairing>::G2;
+
+    #[test]
+    fn test_envelope() {
+        let mut rng = ark_std::test_rng();
+
+        let envelope = Envelope::<E> {
+            n: 16,
+            t: 7,
+            gamma_g2: G2::rand(&mut rng),
+            sa1: [G1::rand(&mut rng), G1::generator()],
+            sa2: [G2::rand(&mut rng); 6],
+            proof: None,
+            tag: None,
+            timelock: None,
+            dem: Dem::ChaCha20Poly1305,
+            nonce: vec![7u8; 12],
+            payload: b"Hello, world!".to_vec(),
+        };
+
+        let bytes = envelope.encode();
+        assert_eq!(&bytes[..4], MAGIC);
+        assert_eq!(Envelope::<E>::decode(&bytes).unwrap(), envelope);
+
+        let mut bad = bytes.clone();
+        bad[0] = b'X';
+        assert_eq!(Envelope::<E>::decode(&bad), Err(EnvelopeError::BadMagic));
+
+        let mut bad = bytes.clone();
+        bad[5] = 0;
+        assert_eq!(Envelope::<E>::decode(&bad), Err(EnvelopeError::WrongCurve(0)));
+
+        let mut bad = bytes.clone();
+        bad[7] = 9;
+        assert_eq!(Envelope::<E>::decode(&bad), Err(EnvelopeError::UnknownDem(9)));
+
+        assert_eq!(Envelope::<E>::decode(&bytes[..bytes.len() - 1]), Err(EnvelopeError::Truncated));
+
+        let mut bad = bytes.clone();
+        bad.push(0);
+        assert_eq!(Envelope::<E>::decode(&bad), Err(EnvelopeError::TrailingData));
+
+        let mut bad = bytes.clone();
+        bad[6] = 8;
+        assert_eq!(Envelope::<E>::decode(&bad), Err(EnvelopeError::UnknownFlags(8)));
+
+        // a byte slipped in at the end of the header, with its length fixed up
+        let kem_len = u32::from_be_bytes(bytes[16..20].try_into().unwrap()) as usize;
+        let mut bad = bytes.clone();
+        bad[16..20].copy_from_slice(&(kem_len as u32 + 1).to_be_bytes());
+        bad.insert(20 + kem_len, 0);
+        assert_eq!(Envelope::<E>::decode(&bad), Err(EnvelopeError::MalformedHeader));
+
+        let proof = CiphertextProof::<E> {
+            c: UniformRand::rand(&mut rng),
+            z: [UniformRand::rand(&mut rng); 6],
+        };
+        let envelope = envelope.with_proof(proof);
+        let bytes = envelope.encode();
+        assert_eq!(bytes[6], FLAG_PROOF);
+        assert_eq!(Envelope::<E>::decode(&bytes).unwrap(), envelope);
+
+        let envelope = envelope.with_tag(b"block 42".to_vec());
+        let bytes = envelope.encode();
+        assert_eq!(bytes[6], FLAG_PROOF | FLAG_TAG);
+        assert_eq!(Envelope::<E>::decode(&bytes).unwrap(), envelope);
+    }
+
+    #[tokio::test]
+    async fn test_seal() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let TestCommittee { params, sk, agg_key: ak, .. } = test_committee::<E>(n, &mut rng).await;
+
+        let ek = EncryptionKey::new(&ak.without_hints(), 1, &params).unwrap();
+        let envelope = Envelope::seal(&ek, Dem::Aes256Gcm, b"Hello, world!", true).unwrap();
+        let envelope = Envelope::<E>::decode(&envelope.encode()).unwrap();
+        assert_eq!((envelope.n, envelope.t), (n, 1));
+        assert!(verify_ciphertext(&envelope.gamma_g2, &envelope.sa1, &envelope.sa2, envelope.t, envelope.proof.as_ref().unwrap(), &ak, &params));
+
+        let parts: Vec<G2> = sk.iter().map(|sk| sk.partial_decryption(envelope.gamma_g2)).collect();
+        let dec_key = agg_dec(&parts, &envelope.sa1, &envelope.sa2, envelope.t, n, &[true, true, false, false], &ak, &params).await.unwrap();
+
+        let key = envelope.dem.derive_key(&dec_key);
+        let msg = envelope.dem.decrypt(&key, &envelope.nonce, &envelope.payload, &envelope.ad()).unwrap();
+        assert_eq!(msg, b"Hello, world!");
+
+        // n is bound to the payload like everything else before the nonce
+        let mut altered = envelope.clone();
+        altered.n += 1;
+        assert_eq!(altered.dem.decrypt(&key, &altered.nonce, &altered.payload, &altered.ad()), Err(Error::Dem));
+
+        // opened by the beacon alone
+        let beacon = Beacon::<E>::new(&mut rng);
+        let envelope = Envelope::seal_timelock(&ek, Dem::ChaCha20Poly1305, b"Hello, world!", &beacon.pk, 7).unwrap();
+        let bytes = envelope.encode();
+        assert_eq!(bytes[6], FLAG_TIMELOCK);
+        let envelope = Envelope::<E>::decode(&bytes).unwrap();
+
+        let dec_key = envelope.timelock.as_ref().unwrap().open(&beacon.pk, &beacon.sign(7)).unwrap();
+        let key = envelope.dem.derive_key(&dec_key);
+        let msg = envelope.dem.decrypt(&key, &envelope.nonce, &envelope.payload, &envelope.ad()).unwrap();
+        assert_eq!(msg, b"Hello, world!");
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+
+use crate::{curve::FileError, envelope::EnvelopeError, kzg};
+
+/// Errors of the library functions, `code` gives each variant a stable number for the api
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A point, scalar or proof can't be deserialized, holds the name of the field
+    MalformedPoint(String),
+    /// The domain size isn't a power of two above 1
+    NotPowerOfTwo(usize),
+    /// A list doesn't have the length the committee size calls for
+    SizeMismatch {
+        what: &'static str,
+        expected: usize,
+        got: usize,
+    },
+    /// The threshold isn't below the committee size
+    Threshold { t: usize, n: usize },
+    /// A party index outside of the committee
+    PartyIndex { id: usize, n: usize },
+    /// There is no lagrange or is_valid helper for a committee of this size
+    MissingHelper(usize),
+    /// The transcript is too short for the committee size or the threshold
+    Kzg(kzg::Error),
+    Envelope(EnvelopeError),
+    File(FileError),
+    UnknownDem(u32),
+    /// The `CiphertextProof` doesn't verify
+    InvalidProof,
+    /// A `CiphertextProof` is required but none was given
+    MissingProof,
+    /// The dem rejected the key, the nonce or the tag
+    Dem,
+    /// Fewer than `t` partial decryptions are selected besides the dummy party's
+    NotEnoughParts { t: usize, got: usize },
+    /// A committee needs the dummy party and at least one member
+    TooFewParties(usize),
+    /// A party of a weighted committee has weight 0, holds the index of the party
+    ZeroWeight(usize),
+    /// No committee is registered with this id or hash
+    UnknownCommittee(String),
+    /// Another committee is registered with this id
+    CommitteeExists(String),
+    /// A ciphertext for a tag was asked for or came with a `CiphertextProof`, nobody knows the
+    /// discrete log of its `gamma_g2`
+    TaggedProof,
+    /// The beacon signature of this round doesn't verify
+    InvalidBeacon(u64),
+    /// A partial decryption blinded to a requester is required but no requester key was given
+    MissingRequester,
+    /// A `CiphertextProof` was sent or asked for along with the keys of its committee rather than a
+    /// reference to a registered one, whose aggregate key is already computed
+    UnregisteredCommittee,
+    /// The registry holds as many committees as it may, holds the limit
+    RegistryFull(usize),
+    /// A committee can't be written to the registry directory, holds the cause
+    Storage(String),
+    /// The `gamma_g2` of a ciphertext said to be for a tag isn't the one of the tag
+    TagMismatch,
+}
+
+impl Error {
+    pub fn code(&self) -> u32 {
+        match self {
+            Error::MalformedPoint(_) => 1,
+            Error::NotPowerOfTwo(_) => 2,
+            Error::SizeMismatch { .. } => 3,
+            Error::Threshold { .. } => 4,
+            Error::PartyIndex { .. } => 5,
+            Error::MissingHelper(_) => 6,
+            Error::Kzg(_) => 7,
+            Error::Envelope(_) => 8,
+            Error::File(_) => 9,
+            Error::UnknownDem(_) => 10,
+            Error::InvalidProof => 11,
+            Error::MissingProof => 12,
+            Error::Dem => 13,
+            Error::NotEnoughParts { .. } => 14,
+            Error::TooFewParties(_) => 15,
+            Error::ZeroWeight(_) => 16,
+            Error::UnknownCommittee(_) => 17,
+            Error::CommitteeExists(_) => 18,
+            Error::TaggedProof => 19,
+            Error::InvalidBeacon(_) => 20,
+            Error::MissingRequester => 21,
+            Error::UnregisteredCommittee => 22,
+            Error::RegistryFull(_) => 23,
+            Error::Storage(_) => 24,
+            Error::TagMismatch => 25,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MalformedPoint(what) => write!(f, "can't deserialize {}", what),
+            Error::NotPowerOfTwo(n) => write!(f, "n should be a power of two above 1, got {}", n),
+            Error::SizeMismatch { what, expected, got } => write!(f, "expected {} {}, got {}", expected, what, got),
+            Error::Threshold { t, n } => write!(f, "t should be below n, got t = {} and n = {}", t, n),
+            Error::PartyIndex { id, n } => write!(f, "party {} is out of a committee of {}", id, n),
+            Error::MissingHelper(n) => write!(f, "there is no helper for n = {}", n),
+            Error::Kzg(kzg::Error::DegreeIsZero) => write!(f, "the transcript has degree 0"),
+            Error::Kzg(kzg::Error::TooManyCoefficients { num_coefficients, num_powers }) => {
+                write!(f, "the transcript has {} powers, {} needed", num_powers, num_coefficients)
+            }
+            Error::Envelope(err) => write!(f, "can't decode envelope, {:?}", err),
+            Error::File(FileError::WrongCurve(id)) => write!(f, "the file was written for curve {}", id),
+            Error::File(FileError::Malformed) => write!(f, "can't deserialize the file"),
+            Error::UnknownDem(dem) => write!(f, "unknown dem {}", dem),
+            Error::InvalidProof => write!(f, "the ciphertext proof doesn't verify"),
+            Error::MissingProof => write!(f, "a ciphertext proof is required"),
+            Error::Dem => write!(f, "the dem failed to encrypt or decrypt"),
+            Error::NotEnoughParts { t, got } => write!(f, "{} partial decryptions, {} needed", got, t),
+            Error::TooFewParties(n) => write!(f, "n should be at least 2, got {}", n),
+            Error::ZeroWeight(party) => write!(f, "party {} has weight 0", party),
+            Error::UnknownCommittee(id) => write!(f, "no committee is registered as {}", id),
+            Error::CommitteeExists(id) => write!(f, "another committee is registered as {}", id),
+            Error::TaggedProof => write!(f, "a ciphertext for a tag can't carry a proof"),
+            Error::InvalidBeacon(round) => write!(f, "the beacon signature of round {} doesn't verify", round),
+            Error::MissingRequester => write!(f, "a requester key is required"),
+            Error::UnregisteredCommittee => write!(f, "a ciphertext proof is only checked against a registered committee"),
+            Error::RegistryFull(max) => write!(f, "{} committees are registered already", max),
+            Error::Storage(err) => write!(f, "can't store the committee: {}", err),
+            Error::TagMismatch => write!(f, "gamma_g2 isn't the one of the tag"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<kzg::Error> for Error {
+    fn from(err: kzg::Error) -> Self {
+        Error::Kzg(err)
+    }
+}
+
+impl From<EnvelopeError> for Error {
+    fn from(err: EnvelopeError) -> Self {
+        Error::Envelope(err)
+    }
+}
+
+impl From<FileError> for Error {
+    fn from(err: FileError) -> Self {
+        Error::File(err)
+    }
+}
+
+/// `NotPowerOfTwo` unless `n` is a power of two above 1, used for the domain sizes of the helpers and hints
+pub fn check_n(n: usize) -> Result<(), Error> {
+    if n < 2 || !n.is_power_of_two() {
+        return Err(Error::NotPowerOfTwo(n));
+    }
+    Ok(())
+}
+
+/// `TooFewParties` unless `n` is at least 2, the smallest committee has the dummy party and one member
+pub fn check_parties(n: usize) -> Result<(), Error> {
+    if n < 2 {
+        return Err(Error::TooFewParties(n));
+    }
+    Ok(())
+}
+
+/// Checks a committee of `n` parties with threshold `t` whose members sent `num_pks` keys, party 0 is
+/// the dummy one so there are `n - 1` members. `n` needn't be a power of two, see `utils::domain_size`
+pub fn check_committee(n: usize, t: usize, num_pks: usize) -> Result<(), Error> {
+    check_parties(n)?;
+    if num_pks != n - 1 {
+        return Err(Error::SizeMismatch { what: "public keys", expected: n - 1, got: num_pks });
+    }
+    if t >= n {
+        return Err(Error::Threshold { t, n });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_committee() {
+        assert_eq!(check_committee(8, 3, 7), Ok(()));
+        assert_eq!(check_committee(22, 14, 21), Ok(()));
+        assert_eq!(check_committee(0, 0, 0), Err(Error::TooFewParties(0)));
+        assert_eq!(check_committee(1, 0, 0), Err(Error::TooFewParties(1)));
+        assert_eq!(check_n(6), Err(Error::NotPowerOfTwo(6)));
+        assert_eq!(check_committee(8, 3, 8), Err(Error::SizeMismatch { what: "public keys", expected: 7, got: 8 }));
+        assert_eq!(check_committee(8, 8, 7), Err(Error::Threshold { t: 8, n: 8 }));
+    }
+}
@@ -0,0 +1,195 @@
+use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, Group};
+use ark_ff::{BigInteger, PrimeField, Zero};
+
+use crate::{encryption::Ciphertext, error::Error};
+
+/// Size of a G1 point for the EVM precompiles, `x || y`
+pub const G1_LEN: usize = 64;
+/// Size of a G2 point for the EVM precompiles, `x.c1 || x.c0 || y.c1 || y.c0`
+pub const G2_LEN: usize = 128;
+
+type G1 = <Bn254 as Pairing>::G1;
+type G2 = <Bn254 as Pairing>::G2;
+
+fn fq_bytes(f: &Fq, out: &mut Vec<u8>) {
+    out.extend_from_slice(&f.into_bigint().to_bytes_be());
+}
+
+fn fq_from_bytes(bytes: &[u8]) -> Option<Fq> {
+    let f = Fq::from_be_bytes_mod_order(bytes);
+    // reject non-canonical encodings like the precompiles do
+    if f.into_bigint().to_bytes_be() != bytes {
+        return None;
+    }
+    Some(f)
+}
+
+/// Uncompressed big-endian encoding of `p`, the point at infinity is all zeros
+pub fn g1_bytes(p: &G1) -> Vec<u8> {
+    let p = p.into_affine();
+    if p.is_zero() {
+        return vec![0u8; G1_LEN];
+    }
+
+    let mut out = Vec::with_capacity(G1_LEN);
+    fq_bytes(&p.x, &mut out);
+    fq_bytes(&p.y, &mut out);
+    out
+}
+
+/// Uncompressed big-endian encoding of `p` with the imaginary parts first, the point at infinity is all zeros
+pub fn g2_bytes(p: &G2) -> Vec<u8> {
+    let p = p.into_affine();
+    if p.is_zero() {
+        return vec![0u8; G2_LEN];
+    }
+
+    let mut out = Vec::with_capacity(G2_LEN);
+    fq_bytes(&p.x.c1, &mut out);
+    fq_bytes(&p.x.c0, &mut out);
+    fq_bytes(&p.y.c1, &mut out);
+    fq_bytes(&p.y.c0, &mut out);
+    out
+}
+
+/// Reads what `g1_bytes` wrote, `MalformedPoint` unless the point is on the curve
+pub fn g1_from_bytes(bytes: &[u8]) -> Result<G1, Error> {
+    if bytes.len() != G1_LEN {
+        return Err(Error::SizeMismatch { what: "bytes of a G1 point", expected: G1_LEN, got: bytes.len() });
+    }
+    if bytes.iter().all(|b| *b == 0) {
+        return Ok(G1::zero());
+    }
+
+    g1_from_coords(bytes).ok_or(Error::MalformedPoint("G1 point".to_string()))
+}
+
+fn g1_from_coords(bytes: &[u8]) -> Option<G1> {
+    let p = G1Affine::new_unchecked(fq_from_bytes(&bytes[..32])?, fq_from_bytes(&bytes[32..])?);
+    if !p.is_on_curve() {
+        return None;
+    }
+    Some(p.into_group())
+}
+
+/// Reads what `g2_bytes` wrote, `MalformedPoint` unless the point is on the curve and in the prime order subgroup
+pub fn g2_from_bytes(bytes: &[u8]) -> Result<G2, Error> {
+    if bytes.len() != G2_LEN {
+        return Err(Error::SizeMismatch { what: "bytes of a G2 point", expected: G2_LEN, got: bytes.len() });
+    }
+    if bytes.iter().all(|b| *b == 0) {
+        return Ok(G2::zero());
+    }
+
+    g2_from_coords(bytes).ok_or(Error::MalformedPoint("G2 point".to_string()))
+}
+
+fn g2_from_coords(bytes: &[u8]) -> Option<G2> {
+    let x = Fq2::new(fq_from_bytes(&bytes[32..64])?, fq_from_bytes(&bytes[..32])?);
+    let y = Fq2::new(fq_from_bytes(&bytes[96..])?, fq_from_bytes(&bytes[64..96])?);
+    let p = G2Affine::new_unchecked(x, y);
+    if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+        return None;
+    }
+    Some(p.into_group())
+}
+
+/// Ciphertext header laid out as `gamma_g2 || sa1[0] || sa1[1] || sa2[0] .. sa2[5]`
+pub fn export_header(ct: &Ciphertext<Bn254>) -> Vec<u8> {
+    let mut out = Vec::with_capacity(7 * G2_LEN + 2 * G1_LEN);
+    out.extend_from_slice(&g2_bytes(&ct.gamma_g2));
+    for p in ct.sa1.iter() {
+        out.extend_from_slice(&g1_bytes(p));
+    }
+    for p in ct.sa2.iter() {
+        out.extend_from_slice(&g2_bytes(p));
+    }
+    out
+}
+
+pub fn export_part_dec(part_dec: &G2) -> Vec<u8> {
+    g2_bytes(part_dec)
+}
+
+/// Input of the pairing precompile (`0x08`) that checks `e(bls_pk, gamma_g2) == e(g1, part_dec)` the way
+/// `part_verify` does, as `e(bls_pk, gamma_g2) * e(-g1, part_dec) == 1`
+pub fn part_verify_calldata(gamma_g2: &G2, bls_pk: &G1, part_dec: &G2) -> Vec<u8> {
+    let mut out = Vec::with_capacity(2 * (G1_LEN + G2_LEN));
+    out.extend_from_slice(&g1_bytes(bls_pk));
+    out.extend_from_slice(&g2_bytes(gamma_g2));
+    out.extend_from_slice(&g1_bytes(&-G1Projective::generator()));
+    out.extend_from_slice(&g2_bytes(part_dec));
+    out
+}
+
+/// What the pairing precompile returns for `calldata`, for testing the exported data off chain
+pub fn pairing_check(calldata: &[u8]) -> Result<bool, Error> {
+    if calldata.len() % (G1_LEN + G2_LEN) != 0 {
+        let pairs = calldata.len() / (G1_LEN + G2_LEN);
+        return Err(Error::SizeMismatch { what: "bytes of pairing calldata", expected: (pairs + 1) * (G1_LEN + G2_LEN), got: calldata.len() });
+    }
+
+    let mut a = Vec::new();
+    let mut b: Vec<G2Projective> = Vec::new();
+    for pair in calldata.chunks(G1_LEN + G2_LEN) {
+        a.push(g1_from_bytes(&pair[..G1_LEN])?);
+        b.push(g2_from_bytes(&pair[G1_LEN..])?);
+    }
+
+    Ok(Bn254::multi_pairing(a, b).is_zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    use ark_std::UniformRand;
+
+    use crate::{encryption::encrypt, utils::{test_committee, TestCommittee}};
+
+    type E = Bn254;
+
+    fn fq(s: &str) -> Vec<u8> {
+        Fq::from_str(s).unwrap().into_bigint().to_bytes_be()
+    }
+
+    #[tokio::test]
+    async fn test_evm() {
+        // generators as given in EIP-197
+        let g1 = g1_bytes(&G1::generator());
+        assert_eq!(g1, [fq("1"), fq("2")].concat());
+
+        let g2 = g2_bytes(&G2::generator());
+        assert_eq!(g2, [
+            fq("11559732032986387107991004021392285783925812861821192530917403151452391805634"),
+            fq("10857046999023057135944570762232829481370756359578518086990519993285655852781"),
+            fq("4082367875863433681332203403145435568316851327593401208105741076214120093531"),
+            fq("8495653923123431417604973247489272438418190587263600148770280649306958101930"),
+        ].concat());
+
+        let mut rng = ark_std::test_rng();
+        let p = G2::rand(&mut rng);
+        assert_eq!(g2_from_bytes(&g2_bytes(&p)), Ok(p));
+        assert_eq!(g1_from_bytes(&g1_bytes(&G1::zero())), Ok(G1::zero()));
+        assert_eq!(g1_from_bytes(&[1u8; G1_LEN]), Err(Error::MalformedPoint("G1 point".to_string())));
+
+        let n = 4;
+        let TestCommittee { params, sk, pk, agg_key, .. } = test_committee::<E>(n, &mut rng).await;
+        let ct = encrypt::<E>(&agg_key, 2, &params).unwrap();
+        assert_eq!(export_header(&ct).len(), 7 * G2_LEN + 2 * G1_LEN);
+
+        let part_dec = sk[1].partial_decryption(ct.gamma_g2);
+        assert_eq!(export_part_dec(&part_dec).len(), G2_LEN);
+        assert_eq!(pairing_check(&part_verify_calldata(&ct.gamma_g2, &pk[1].bls_pk, &part_dec)), Ok(true));
+        assert_eq!(pairing_check(&part_verify_calldata(&ct.gamma_g2, &pk[2].bls_pk, &part_dec)), Ok(false));
+
+        let calldata = part_verify_calldata(&ct.gamma_g2, &pk[1].bls_pk, &part_dec);
+        let got = calldata.len() - 1;
+        assert_eq!(
+            pairing_check(&calldata[..got]),
+            Err(Error::SizeMismatch { what: "bytes of pairing calldata", expected: 2 * (G1_LEN + G2_LEN), got })
+        );
+    }
+}
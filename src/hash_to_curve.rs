@@ -0,0 +1,106 @@
+use ark_bls12_381::{g1, g2, G1Projective, G2Projective};
+use ark_ec::{
+    hashing::{curve_maps::wb::{WBConfig, WBMap}, map_to_curve_hasher::MapToCurveBasedHasher, HashToCurve},
+    short_weierstrass::Projective,
+    AffineRepr,
+};
+use ark_ff::field_hashers::DefaultFieldHasher;
+use sha2::Sha256;
+
+/// RFC 9380 suite of `hash_to_g1`
+pub const SUITE_G1: &str = "BLS12381G1_XMD:SHA-256_SSWU_RO_";
+/// RFC 9380 suite of `hash_to_g2`
+pub const SUITE_G2: &str = "BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+/// Domain separation tags of this crate, in the `<app>-V<xx>-CS<yy>-with-<suite>` format the RFC
+/// recommends. Other uses of the same suite should pick their own.
+pub const DST_G1: &[u8] = b"SILENT-THRESHOLD-V01-CS01-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+pub const DST_G2: &[u8] = b"SILENT-THRESHOLD-V01-CS01-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+/// `hash_to_curve` of RFC 9380 to the G1 of BLS12-381 with `dst`, DSTs above 255 bytes are hashed
+/// as the RFC says
+pub fn hash_to_g1(dst: &[u8], msg: &[u8]) -> G1Projective {
+    hash_to_curve::<g1::Config>(dst, msg)
+}
+
+/// `hash_to_curve` of RFC 9380 to the G2 of BLS12-381 with `dst`. `CurveId::hash_to_g2` is this
+/// function on BLS12-381, so its points can be passed to `encryption::encrypt_with_gamma_g2` and
+/// `SecretKey::partial_decryption`.
+pub fn hash_to_g2(dst: &[u8], msg: &[u8]) -> G2Projective {
+    hash_to_curve::<g2::Config>(dst, msg)
+}
+
+/// hash_to_curve with the simplified SWU map through an isogeny and expand_message_xmd over SHA-256,
+/// the `_XMD:SHA-256_SSWU_RO_` suites of any curve arkworks has an isogeny for
+pub(crate) fn hash_to_curve<P: WBConfig>(dst: &[u8], msg: &[u8]) -> Projective<P> {
+    // neither step can fail with the isogenies of the arkworks curves
+    MapToCurveBasedHasher::<Projective<P>, DefaultFieldHasher<Sha256, 128>, WBMap<P>>::new(dst)
+        .and_then(|hasher| hasher.hash(msg))
+        .unwrap()
+        .into_group()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::{Fq, Fq2};
+    use ark_ec::CurveGroup;
+    use ark_ff::PrimeField;
+
+    fn fq(hex: &str) -> Fq {
+        Fq::from_be_bytes_mod_order(&hex::decode(hex.trim_start_matches("0x")).unwrap())
+    }
+
+    fn fq2(hex: &str) -> Fq2 {
+        let (c0, c1) = hex.split_once(',').unwrap();
+        Fq2::new(fq(c0), fq(c1))
+    }
+
+    // appendix J.9.1 and J.10.1 of RFC 9380
+    const DST_G1_QUUX: &[u8] = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+    const DST_G2_QUUX: &[u8] = b"QUUX-V01-CS02-with-BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+    #[test]
+    fn test_hash_to_g1() {
+        let vectors = [
+            (
+                "",
+                "0x052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1",
+                "0x08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265",
+            ),
+            (
+                "abc",
+                "0x03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903",
+                "0x0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d",
+            ),
+        ];
+
+        for (msg, x, y) in vectors {
+            let point = hash_to_g1(DST_G1_QUUX, msg.as_bytes()).into_affine();
+            assert_eq!((point.x, point.y), (fq(x), fq(y)));
+        }
+        assert!(hash_to_g1(DST_G1, b"abc") != hash_to_g1(DST_G1_QUUX, b"abc"));
+    }
+
+    #[test]
+    fn test_hash_to_g2() {
+        let vectors = [
+            (
+                "",
+                "0x0141ebfbdca40eb85b87142e130ab689c673cf60f1a3e98d69335266f30d9b8d4ac44c1038e9dcdd5393faf5c41fb78a,0x05cb8437535e20ecffaef7752baddf98034139c38452458baeefab379ba13dff5bf5dd71b72418717047f5b0f37da03d",
+                "0x0503921d7f6a12805e72940b963c0cf3471c7b2a524950ca195d11062ee75ec076daf2d4bc358c4b190c0c98064fdd92,0x12424ac32561493f3fe3c260708a12b7c620e7be00099a974e259ddc7d1f6395c3c811cdd19f1e8dbf3e9ecfdcbab8d6",
+            ),
+            (
+                "abc",
+                "0x02c2d18e033b960562aae3cab37a27ce00d80ccd5ba4b7fe0e7a210245129dbec7780ccc7954725f4168aff2787776e6,0x139cddbccdc5e91b9623efd38c49f81a6f83f175e80b06fc374de9eb4b41dfe4ca3a230ed250fbe3a2acf73a41177fd8",
+                "0x1787327b68159716a37440985269cf584bcb1e621d3a7202be6ea05c4cfe244aeb197642555a0645fb87bf7466b2ba48,0x00aa65dae3c8d732d10ecd2c50f8a1baf3001578f71c694e03866e9f3d49ac1e1ce70dd94a733534f106d4cec0eddd16",
+            ),
+        ];
+
+        for (msg, x, y) in vectors {
+            let point = hash_to_g2(DST_G2_QUUX, msg.as_bytes()).into_affine();
+            assert_eq!((point.x, point.y), (fq2(x), fq2(y)));
+        }
+        assert!(hash_to_g2(DST_G2, b"abc") != hash_to_g2(DST_G2_QUUX, b"abc"));
+    }
+}
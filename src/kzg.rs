@@ -24,7 +24,7 @@ pub struct UniversalParams<E: Pairing> {
     pub powers_of_h: Vec<E::G2Affine>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum Error {
     /// The degree provided in setup was too small; degree 0 polynomials
     /// are not supported.
@@ -148,7 +148,7 @@ pub fn convert_to_bigints<F: PrimeField>(p: &[F]) -> Vec<F::BigInt> {
         .collect::<Vec<_>>()
 }
 
-fn check_degree_is_too_large(degree: usize, num_powers: usize) -> Result<(), Error> {
+pub(crate) fn check_degree_is_too_large(degree: usize, num_powers: usize) -> Result<(), Error> {
     let num_coefficients = degree + 1;
     if num_coefficients > num_powers {
         Err(Error::TooManyCoefficients {
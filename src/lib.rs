@@ -1,6 +1,16 @@
+pub mod curve;
 pub mod decryption;
+pub mod dem;
 pub mod encryption;
+pub mod envelope;
+pub mod error;
+pub mod evm;
+pub mod hash_to_curve;
 pub mod kzg;
 pub mod setup;
+pub mod signature;
+pub mod timelock;
 pub mod utils;
+pub mod weighted;
+#[cfg(feature = "server")]
 pub mod api;
\ No newline at end of file
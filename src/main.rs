@@ -1,13 +1,20 @@
 use actix_web::{middleware, web, App, HttpServer};
+use ark_bls12_377::Bls12_377;
+use ark_bls12_381::Bls12_381;
+use ark_bn254::Bn254;
 use ark_serialize::CanonicalDeserialize;
 use ark_serialize::Read;
-use clap::{arg, command, Parser};
+use clap::{Parser, ValueEnum};
+use silent_threshold::curve::{read_file, CurveId};
+use silent_threshold::error::Error;
 use silent_threshold::kzg::UniversalParams;
 use silent_threshold::setup::SecretKey;
-use silent_threshold::utils::LagrangePolyHelper;
+use silent_threshold::utils::{IsValidHelper, LagrangePolyHelper};
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::Cursor;
+use std::io;
 
+use silent_threshold::api::registry::Registry;
 use silent_threshold::api::routes::*;
 use silent_threshold::api::types::*;
 
@@ -27,82 +34,130 @@ struct Args {
     api_port: u16,
 
     #[arg(short, long, default_value_t = false)]
-    test: bool
-}
+    test: bool,
 
+    /// Check the hints of every public key sent to /encrypt and /decrypt
+    #[arg(long, default_value_t = false)]
+    check_pks: bool,
 
-#[actix_web::main]
-async fn main() -> std::io::Result<()> {
-    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
-    
-    let args = Args::parse();
+    /// Only partially decrypt and decrypt ciphertexts that come with a proof, or for tags.
+    /// `--require-proof false` also serves bare `gamma_g2`s, which lets anyone get any point signed
+    #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+    require_proof: bool,
 
-    let kzg_setup: UniversalParams<E>;
-    {
-        let mut file = File::open(args.transcript).expect("can't open transcript");
+    /// Only issue partial decryptions blinded to the key of a requester
+    #[arg(long, default_value_t = false)]
+    require_requester: bool,
 
-        let mut contents = Vec::new();
-        file.read_to_end(&mut contents).expect("can't read transcript.json to a string");
+    /// Directory the committees sent to /register are written to and loaded from
+    #[arg(long, default_value = "./committees")]
+    committees: String,
 
-        let cursor = Cursor::new(contents);
-        kzg_setup = UniversalParams::<E>::deserialize_compressed(cursor).expect("Unable to deserialize kzg_setup");
-        println!("powers_of_g: {}, powers_of_h: {}", kzg_setup.powers_of_g.len(), kzg_setup.powers_of_h.len());
-        drop(file);
-    }
+    /// Most committees /register takes, those in the directory included
+    #[arg(long, default_value_t = 1024)]
+    max_committees: usize,
 
-    let sk: SecretKey<E>;
-    {
-        let mut file = File::open(args.bls_key).expect("Can't open the file!");
-        let mut contents = Vec::new();
-        file.read_to_end(&mut contents).expect("Can't read the file!");
-        let cursor = Cursor::new(contents);
-        sk = SecretKey::<E>::deserialize_compressed(cursor).expect("Unable to deserialize the data!");
-        drop(file);
-    }
+    /// Curve of the transcript, the key and the helpers
+    #[arg(long, value_enum, default_value_t = Curve::Bls12_381)]
+    curve: Curve
+}
 
-    let mut lagrange_helpers = Vec::new();
-    if !args.test {
-        let lagrange_paths = std::fs::read_dir("./lagrangehelpers").unwrap();
-        for path in lagrange_paths {
-            let p = path.unwrap().path();
-            
-            let mut file = File::open(p.to_str().unwrap()).unwrap();
-            let mut contents = Vec::new();
-            file.read_to_end(&mut contents).expect("Can't read the file!");
-            let cursor = Cursor::new(contents);
-
-            let lagrange = LagrangePolyHelper::deserialize_compressed(cursor).unwrap();
-            lagrange_helpers.push(lagrange);
-            log::info!("{}", p.to_str().unwrap());
-            drop(file);
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Curve {
+    #[value(name = "bls12-381")]
+    Bls12_381,
+    #[value(name = "bls12-377")]
+    Bls12_377,
+    #[value(name = "bn254")]
+    Bn254,
+}
+
+
+/// Loads the per-`n` helpers stored in `dir`, keyed by the domain size `n` they are named after,
+/// so that a missing size doesn't shift the others. In test mode only `n = 2` is loaded.
+fn load_helpers<E: CurveId, T: CanonicalDeserialize>(dir: &str, test: bool) -> io::Result<HashMap<usize, T>> {
+    let mut sizes: Vec<usize> = Vec::new();
+    if !test {
+        for path in std::fs::read_dir(dir)? {
+            let name = path?.file_name();
+            match name.to_str().and_then(|s| s.parse::<usize>().ok()) {
+                Some(n) if n >= 2 && n.is_power_of_two() => sizes.push(n),
+                _ => log::warn!("skipping {}/{:?}", dir, name),
+            }
         }
     } else {
-        let mut file = File::open("./lagrangehelpers/2").unwrap();
-        let mut contents = Vec::new();
-        file.read_to_end(&mut contents).expect("Can't read the file!");
-        let cursor = Cursor::new(contents);
-
-        let lagrange = LagrangePolyHelper::deserialize_compressed(cursor).unwrap();
-        lagrange_helpers.push(lagrange);
-        drop(file);
+        sizes.push(2);
     }
-    
 
-    let data = web::Data::new(Data { kzg_setup, sk, lagrange_helpers });
+    let mut helpers = HashMap::new();
+    for n in sizes {
+        let p = format!("{}/{}", dir, n);
+
+        let helper = read_curve_file::<E, T>(&p)?;
+        helpers.insert(n, helper);
+        log::info!("{}", p);
+    }
+
+    Ok(helpers)
+}
+
+/// Reads a transcript, key or helper written for `E`
+fn read_curve_file<E: CurveId, T: CanonicalDeserialize>(path: &str) -> io::Result<T> {
+    let mut file = File::open(path)
+        .map_err(|err| io::Error::new(err.kind(), format!("can't open {}: {}", path, err)))?;
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents)?;
+
+    read_file::<E, T>(&contents).map_err(|err| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}, the server runs on {}", path, Error::from(err), E::NAME))
+    })
+}
+
+async fn serve<E: CurveId>(args: Args) -> std::io::Result<()> {
+    let kzg_setup: UniversalParams<E> = read_curve_file::<E, _>(&args.transcript)?;
+    println!("powers_of_g: {}, powers_of_h: {}", kzg_setup.powers_of_g.len(), kzg_setup.powers_of_h.len());
 
-    log::info!("starting HTTP server at http://localhost:{}", args.api_port);
+    let sk: SecretKey<E> = read_curve_file::<E, _>(&args.bls_key)?;
+
+    let lagrange_helpers: HashMap<usize, LagrangePolyHelper<E>> = load_helpers::<E, _>("./lagrangehelpers", args.test)?;
+    let is_valid_helpers: HashMap<usize, IsValidHelper<E>> = load_helpers::<E, _>("./isvalidhelpers", args.test)?;
+
+    let committees = Registry::new(Some(args.committees.clone().into()), args.max_committees);
+    let data = web::Data::new(Data { kzg_setup, sk, lagrange_helpers, is_valid_helpers, check_pks: args.check_pks, require_proof: args.require_proof, require_requester: args.require_requester, committees });
+    let loaded = data.committees.load()?;
+    log::info!("loaded {} committees from {}", loaded, args.committees);
+
+    log::info!("starting HTTP server on {} at http://localhost:{}", E::NAME, args.api_port);
     HttpServer::new(move || {
         App::new()
             .wrap(middleware::Logger::default())
             .app_data(web::JsonConfig::default().limit(240000)) // <- limit size of the payload (global configuration)
             .app_data(Data::clone(&data))
-            .service(web::resource("/encrypt").route(web::post().to(encrypt_route)))
-            .service(web::resource("/partdec").route(web::post().to(decrypt_part_route)))
-            .service(web::resource("/decrypt").route(web::post().to(decrypt_route)))
-            .service(web::resource("/verifydec").route(web::post().to(verify_part_route)))
-            .service(web::resource("/getpk").route(web::post().to(get_pk_route)))
+            .service(web::resource("/encrypt").route(web::post().to(encrypt_route::<E>)))
+            .service(web::resource("/partdec").route(web::post().to(decrypt_part_route::<E>)))
+            .service(web::resource("/decrypt").route(web::post().to(decrypt_route::<E>)))
+            .service(web::resource("/verifydec").route(web::post().to(verify_part_route::<E>)))
+            .service(web::resource("/verifydecs").route(web::post().to(batch_verify_part_route::<E>)))
+            .service(web::resource("/getpk").route(web::post().to(get_pk_route::<E>)))
+            .service(web::resource("/getaggkey").route(web::post().to(get_agg_key_route::<E>)))
+            .service(web::resource("/getenckey").route(web::post().to(get_enc_key_route::<E>)))
+            .service(web::resource("/isvalid").route(web::post().to(is_valid_route::<E>)))
+            .service(web::resource("/register").route(web::post().to(register_route::<E>)))
     })
     .bind(("127.0.0.1", args.api_port))?
     .run()
     .await
 }
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+    
+    let args = Args::parse();
+
+    match args.curve {
+        Curve::Bls12_381 => serve::<Bls12_381>(args).await,
+        Curve::Bls12_377 => serve::<Bls12_377>(args).await,
+        Curve::Bn254 => serve::<Bn254>(args).await,
+    }
+}
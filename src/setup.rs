@@ -1,14 +1,18 @@
-use ark_bls12_381::Bls12_381;
 use ark_ec::pairing::PairingOutput;
 use ark_ec::{pairing::Pairing, Group};
 use ark_poly::DenseUVPolynomial;
 use ark_poly::{domain::EvaluationDomain, univariate::DensePolynomial, Radix2EvaluationDomain};
 use ark_serialize::*;
 use ark_std::{rand::RngCore, One, UniformRand, Zero};
+use rand::rngs::OsRng;
 use std::ops::{Mul, Sub};
-use crate::api::types::E as Q;
-use crate::kzg::{UniversalParams, KZG10};
-use crate::utils::LagrangePolyHelper;
+use crate::decryption::{blinded_part_dec_challenge, part_dec_challenge, BlindedPartDec, BlindedPartDecProof, PartDecProof};
+use crate::curve::CurveId;
+use crate::encryption::{tag_gamma_g2, verify_ciphertext, CiphertextProof};
+use crate::error::{check_n, check_parties, Error};
+use crate::kzg::{check_degree_is_too_large, UniversalParams, KZG10};
+use crate::signature::message_point;
+use crate::utils::{domain_size, spawn, LagrangePolyHelper};
 
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
 pub struct SecretKey<E: Pairing> {
@@ -25,9 +29,16 @@ pub struct PublicKey<E: Pairing> {
     pub sk_li_by_tau: E::G1,    //hint
 }
 
-#[derive(Clone)]
+/// Serialized once it is preprocessed, the hints of `pk` and `agg_sk_li_by_z` make most of its
+/// size and only `agg_dec` needs them, see `without_hints`
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
 pub struct AggregateKey<E: Pairing> {
+    /// keys of the `domain_size(n)` slots, the ones from `n` on are the nullified padding. Empty
+    /// in a key without hints
     pub pk: Vec<PublicKey<E>>,
+    /// number of parties, the dummy one included
+    pub n: usize,
+    /// empty in a key without hints
     pub agg_sk_li_by_z: Vec<E::G1>,
     pub ask: E::G1,
     pub z_g2: E::G2,
@@ -68,14 +79,23 @@ impl<E: Pairing> SecretKey<E> {
         self.sk = E::ScalarField::one()
     }
 
-    pub async fn get_pk(&self, id: usize, params: &UniversalParams<E>, n: usize, lagrange_polys: &Vec<DensePolynomial<E::ScalarField>>) -> PublicKey<E> {
+    pub async fn get_pk(&self, id: usize, params: &UniversalParams<E>, n: usize, lagrange_polys: &Vec<DensePolynomial<E::ScalarField>>) -> Result<PublicKey<E>, Error> {
+        check_n(n)?;
+        if id >= n {
+            return Err(Error::PartyIndex { id, n });
+        }
+        if lagrange_polys.len() != n {
+            return Err(Error::SizeMismatch { what: "lagrange polynomials", expected: n, got: lagrange_polys.len() });
+        }
+        check_degree_is_too_large(n - 1, params.powers_of_g.len())?;
+
         let domain = Radix2EvaluationDomain::<E::ScalarField>::new(n).unwrap();
 
         let li: DensePolynomial<<E as Pairing>::ScalarField> = lagrange_polys[id].clone();
 
         let sk_async = self.sk.clone();
 
-        let bls_pk = tokio::spawn(async move {
+        let bls_pk = spawn(async move {
             E::G1::generator() * sk_async
         });
 
@@ -87,7 +107,7 @@ impl<E: Pairing> SecretKey<E> {
             let sk_async = self.sk.clone();
             let params_async = params.clone();
             let lagrange_polys_async = lagrange_polys.clone();
-            let sk_li_by_z = tokio::spawn(async move {
+            let sk_li_by_z = spawn(async move {
                 let num = if id == j {
                     li_async.mul(&li_async).sub(&li_async)
                 } else {
@@ -112,7 +132,7 @@ impl<E: Pairing> SecretKey<E> {
         let sk_async = self.sk.clone();
         let params_async = params.clone();
 
-        let sk_li_by_tau = tokio::spawn(async move {
+        let sk_li_by_tau = spawn(async move {
             let f: DensePolynomial<<E as Pairing>::ScalarField> = DensePolynomial::from_coefficients_vec(li_async.coeffs[1..].to_vec());
             let sk_times_f = &f * sk_async;
             // let sk_li_by_tau =
@@ -126,7 +146,7 @@ impl<E: Pairing> SecretKey<E> {
         let params_async = params.clone();
         let f_async = f.clone();
 
-        let sk_li = tokio::spawn(async move {
+        let sk_li = spawn(async move {
             KZG10::commit_g1(&params_async, &f_async[0])
                 .expect("commitment failed")
                 .into()
@@ -136,7 +156,7 @@ impl<E: Pairing> SecretKey<E> {
         let params_async = params.clone();
         let mut f_async = f.clone();
 
-        let sk_li_minus_0 = tokio::spawn(async move {
+        let sk_li_minus_0 = spawn(async move {
             f_async[0].coeffs[0] = E::ScalarField::zero();
             // let sk_li_minus0 =
             KZG10::commit_g1(&params_async, &f_async[0])
@@ -148,42 +168,148 @@ impl<E: Pairing> SecretKey<E> {
             sk_li_by_z.push(task.await.unwrap());
         }
 
-        PublicKey {
+        Ok(PublicKey {
             id,
             bls_pk: bls_pk.await.unwrap(),
             sk_li: sk_li.await.unwrap(),
             sk_li_minus0: sk_li_minus_0.await.unwrap(),
             sk_li_by_z,
             sk_li_by_tau: sk_li_by_tau.await.unwrap(),
-        }
+        })
     }
 
     pub fn partial_decryption(&self, gamma_g2: E::G2) -> E::G2 {
         gamma_g2 * self.sk // kind of a bls signature on gamma_g2
     }
+
+    /// Partial decryption along with a `PartDecProof` that it matches `bls_pk`
+    pub fn partial_decryption_with_proof(&self, gamma_g2: E::G2) -> (E::G2, PartDecProof<E>) {
+        let part_dec = self.partial_decryption(gamma_g2);
+        let bls_pk = E::G1::generator() * self.sk;
+
+        let r = E::ScalarField::rand(&mut OsRng);
+        let c = part_dec_challenge::<E>(&bls_pk, &gamma_g2, &part_dec, &(E::G1::generator() * r), &(gamma_g2 * r));
+
+        (part_dec, PartDecProof { c, z: r + c * self.sk })
+    }
+
+    /// Partial decryption blinded to the key of a requester, along with a `BlindedPartDecProof` that it
+    /// matches `bls_pk`, see `decryption::RequesterKey`
+    pub fn blinded_partial_decryption(&self, gamma_g2: E::G2, requester_pk: E::G2) -> (BlindedPartDec<E>, BlindedPartDecProof<E>) {
+        let mut rng = OsRng;
+        let bls_pk = E::G1::generator() * self.sk;
+
+        let r = E::ScalarField::rand(&mut rng);
+        let blinded = BlindedPartDec {
+            c1: E::G2::generator() * r,
+            c2: self.partial_decryption(gamma_g2) + requester_pk * r,
+        };
+
+        let nonces = [E::ScalarField::rand(&mut rng), E::ScalarField::rand(&mut rng)];
+        let r_g1 = E::G1::generator() * nonces[0];
+        let r_g2 = [E::G2::generator() * nonces[1], gamma_g2 * nonces[0] + requester_pk * nonces[1]];
+        let c = blinded_part_dec_challenge::<E>(&bls_pk, &gamma_g2, &requester_pk, &blinded, &r_g1, &r_g2);
+
+        let proof = BlindedPartDecProof { c, z: [nonces[0] + c * self.sk, nonces[1] + c * r] };
+        (blinded, proof)
+    }
+
+    /// Partial decryption for a ciphertext from `encrypt_cca`, `InvalidProof` unless the header is well formed.
+    /// Any relation an honest header satisfies can also be satisfied for an arbitrary `gamma_g2` since
+    /// `encrypt` never needs `gamma`, so only the proof of knowledge of `gamma` keeps a committee member
+    /// from signing arbitrary points.
+    #[allow(clippy::too_many_arguments)]
+    pub fn checked_partial_decryption(
+        &self,
+        gamma_g2: E::G2,
+        sa1: &[E::G1; 2],
+        sa2: &[E::G2; 6],
+        t: usize,
+        proof: &CiphertextProof<E>,
+        apk: &AggregateKey<E>,
+        params: &UniversalParams<E>,
+    ) -> Result<E::G2, Error> {
+        if t >= apk.n {
+            return Err(Error::Threshold { t, n: apk.n });
+        }
+        if !verify_ciphertext(&gamma_g2, sa1, sa2, t, proof, apk, params) {
+            return Err(Error::InvalidProof);
+        }
+
+        Ok(self.partial_decryption(gamma_g2))
+    }
 }
 
-pub fn get_pk_exp(sk: &SecretKey<Q>, id: usize, _n: usize, lagrange_polys: &LagrangePolyHelper) -> PublicKey<Q> {
+impl<E: CurveId> SecretKey<E> {
+    /// Partial decryption of all the ciphertexts from `encrypt_for_tag` for `tag`
+    pub fn partial_decryption_for_tag(&self, tag: &[u8]) -> E::G2 {
+        self.partial_decryption(tag_gamma_g2::<E>(tag))
+    }
+
+    /// BLS signature of `msg`, see `signature::aggregate`
+    pub fn sign(&self, msg: &[u8]) -> E::G2 {
+        self.partial_decryption(message_point::<E>(msg))
+    }
+}
+
+pub fn get_pk_exp<E: Pairing>(sk: &SecretKey<E>, id: usize, n: usize, lagrange_polys: &LagrangePolyHelper<E>) -> Result<PublicKey<E>, Error> {
+    if lagrange_polys.li.len() != n {
+        return Err(Error::MissingHelper(n));
+    }
+    if id >= n {
+        return Err(Error::PartyIndex { id, n });
+    }
+
     let mut sk_li_by_z = lagrange_polys.li_by_z[id].clone();
 
     for idx in 0..sk_li_by_z.len() {
         sk_li_by_z[idx] *= sk.sk;
     }
 
-    PublicKey {
+    Ok(PublicKey {
         id,
-        bls_pk: <Bls12_381 as Pairing>::G1::generator() * sk.sk,
+        bls_pk: E::G1::generator() * sk.sk,
         sk_li: lagrange_polys.li[id] * sk.sk,
         sk_li_minus0: lagrange_polys.li_minus0[id] * sk.sk,
         sk_li_by_z: sk_li_by_z.to_owned(),
         sk_li_by_tau: lagrange_polys.li_by_tau[id] * sk.sk,
+    })
+}
+
+/// Appends nullified dummy parties to the keys of a committee of `pk.len()` parties until every slot
+/// of the domain of `lagrange_polys` is taken. The dummies are selected by `agg_dec` like the one at
+/// slot 0 and anyone can compute their partial decryptions, so `encrypt` and `agg_dec` raise the
+/// threshold by their number.
+pub fn pad_pks<E: Pairing>(mut pk: Vec<PublicKey<E>>, lagrange_polys: &LagrangePolyHelper<E>) -> Result<Vec<PublicKey<E>>, Error> {
+    let size = lagrange_polys.li.len();
+    if pk.len() > size {
+        return Err(Error::SizeMismatch { what: "public keys", expected: size, got: pk.len() });
     }
+
+    let dummy = SecretKey { sk: E::ScalarField::one() };
+    for id in pk.len()..size {
+        pk.push(get_pk_exp(&dummy, id, size, lagrange_polys)?);
+    }
+
+    Ok(pk)
 }
 
 impl<E: Pairing> AggregateKey<E> {
-    pub fn new(pk: Vec<PublicKey<E>>, n: usize, params: &UniversalParams<E>) -> Self {
+    /// `pk` holds the keys of the `n` parties, the dummy party included, padded to `domain_size(n)`
+    /// slots with `pad_pks`
+    pub fn new(pk: Vec<PublicKey<E>>, n: usize, params: &UniversalParams<E>) -> Result<Self, Error> {
+        check_parties(n)?;
+        let size = domain_size(n);
+        if pk.len() != size {
+            return Err(Error::SizeMismatch { what: "public keys", expected: size, got: pk.len() });
+        }
+        if let Some(pki) = pk.iter().find(|pki| pki.sk_li_by_z.len() != size) {
+            return Err(Error::SizeMismatch { what: "sk_li_by_z hints", expected: size, got: pki.sk_li_by_z.len() });
+        }
+        check_degree_is_too_large(size, params.powers_of_h.len())?;
+
         let h_minus1 = params.powers_of_h[0] * (-E::ScalarField::one());
-        let z_g2 = params.powers_of_h[n] + h_minus1;
+        let z_g2 = params.powers_of_h[size] + h_minus1;
 
         // gather sk_li from all public keys
         let mut ask = E::G1::zero();
@@ -192,7 +318,7 @@ impl<E: Pairing> AggregateKey<E> {
         }
 
         let mut agg_sk_li_by_z = vec![];
-        for i in 0..n {
+        for i in 0..size {
             let mut agg_sk_li_by_zi = E::G1::zero();
             for pkj in pk.iter() {
                 agg_sk_li_by_zi += pkj.sk_li_by_z[i];
@@ -200,14 +326,48 @@ impl<E: Pairing> AggregateKey<E> {
             agg_sk_li_by_z.push(agg_sk_li_by_zi);
         }
 
-        AggregateKey {
+        Ok(AggregateKey {
             pk,
+            n,
             agg_sk_li_by_z,
             ask,
             z_g2,
             h_minus1,
             e_gh: E::pairing(params.powers_of_g[0], params.powers_of_h[0]),
+        })
+    }
+
+    /// number of nullified dummy parties after the `n` parties, the threshold `encrypt` and `agg_dec`
+    /// work with is raised by as much since their partial decryptions are public
+    pub fn padding(&self) -> usize {
+        domain_size(self.n) - self.n
+    }
+
+    /// The key with `pk` and `agg_sk_li_by_z` left out, its size no longer grows with `n`. It is
+    /// all `encrypt`, `encrypt_cca` and `verify_ciphertext` need, `agg_dec` refuses it.
+    pub fn without_hints(&self) -> Self {
+        AggregateKey {
+            pk: Vec::new(),
+            n: self.n,
+            agg_sk_li_by_z: Vec::new(),
+            ask: self.ask,
+            z_g2: self.z_g2,
+            h_minus1: self.h_minus1,
+            e_gh: self.e_gh,
+        }
+    }
+
+    /// `SizeMismatch` unless the key holds the hints of all `domain_size(n)` slots, as `agg_dec`
+    /// needs them
+    pub fn check_hints(&self) -> Result<(), Error> {
+        let size = domain_size(self.n);
+        if self.pk.len() != size {
+            return Err(Error::SizeMismatch { what: "public keys", expected: size, got: self.pk.len() });
+        }
+        if self.agg_sk_li_by_z.len() != size {
+            return Err(Error::SizeMismatch { what: "aggregated sk_li_by_z hints", expected: size, got: self.agg_sk_li_by_z.len() });
         }
+        Ok(())
     }
 }
 
@@ -215,15 +375,17 @@ impl<E: Pairing> AggregateKey<E> {
 mod tests {
     use ark_ec::bls12::Bls12;
 
-    use crate::utils::lagrange_poly;
+    use crate::decryption::agg_dec;
+    use crate::encryption::{encrypt, encrypt_cca};
+    use crate::utils::{lagrange_poly, test_committee, TestCommittee};
 
     use super::*;
 
     type E = ark_bls12_381::Bls12_381;
     type UniPoly381 = DensePolynomial<<E as Pairing>::ScalarField>;
 
-    #[test]
-    fn test_setup() {
+    #[tokio::test]
+    async fn test_setup() {
         let mut rng = ark_std::test_rng();
         let n = 4;
         let params = KZG10::<E, UniPoly381>::setup(n, &mut rng).unwrap();
@@ -237,9 +399,91 @@ mod tests {
 
         for i in 0..n {
             sk.push(SecretKey::<E>::new(&mut rng));
-            pk.push(sk[i].get_pk(0, &params, n, &lagrange_polys))
+            pk.push(sk[i].get_pk(0, &params, n, &lagrange_polys).await.unwrap())
         }
 
-        let _ak = AggregateKey::<E>::new(pk, n, &params);
+        let _ak = AggregateKey::<E>::new(pk, n, &params).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_aggregate_key_serialization() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let TestCommittee { params, sk, agg_key: ak, .. } = test_committee::<E>(n, &mut rng).await;
+
+        let mut full = Vec::new();
+        ak.serialize_compressed(&mut full).unwrap();
+        let mut compact = Vec::new();
+        ak.without_hints().serialize_compressed(&mut compact).unwrap();
+        assert!(compact.len() < full.len());
+
+        // encrypt with the key without hints, decrypt with the full one
+        let encryption_key = AggregateKey::<E>::deserialize_compressed(&compact[..]).unwrap();
+        let ct = encrypt::<E>(&encryption_key, 2, &params).unwrap();
+
+        let ak = AggregateKey::<E>::deserialize_compressed(&full[..]).unwrap();
+        let parts: Vec<_> = sk.iter().map(|sk| sk.partial_decryption(ct.gamma_g2)).collect();
+        let dec_key = agg_dec(&parts, &ct.sa1, &ct.sa2, ct.t, n, &[true, true, true, false], &ak, &params).await;
+        assert!(dec_key == Ok(ct.enc_key));
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let TestCommittee { params, sk, pk, agg_key, .. } = test_committee::<E>(n, &mut rng).await;
+
+        let lagrange_polys: Vec<DensePolynomial<<E as Pairing>::ScalarField>> = (0..n)
+            .map(|j| lagrange_poly(n, j))
+            .collect();
+        assert!(sk[1].get_pk(n, &params, n, &lagrange_polys).await.err() == Some(Error::PartyIndex { id: n, n }));
+        assert!(sk[1].get_pk(0, &params, 3, &lagrange_polys).await.err() == Some(Error::NotPowerOfTwo(3)));
+
+        let ak = AggregateKey::<E>::new(pk[1..].to_vec(), n, &params);
+        assert!(ak.err() == Some(Error::SizeMismatch { what: "public keys", expected: n, got: n - 1 }));
+
+        // a transcript too short for the committee
+        let short = KZG10::<E, UniPoly381>::setup(2, &mut rng).unwrap();
+        let ak = AggregateKey::<E>::new(pk, n, &short);
+        assert!(matches!(ak.err(), Some(Error::Kzg(_))));
+
+        let ak = agg_key;
+        assert!(encrypt::<E>(&ak, n, &params).err() == Some(Error::Threshold { t: n, n }));
+
+        let ct = encrypt::<E>(&ak, 1, &params).unwrap();
+        let dec_key = agg_dec(&[ct.gamma_g2; 4], &ct.sa1, &ct.sa2, ct.t, n, &[true; 4], &ak.without_hints(), &params).await;
+        assert!(dec_key.err() == Some(Error::SizeMismatch { what: "public keys", expected: n, got: 0 }));
+
+        let ct = encrypt::<E>(&ak, 1, &params).unwrap();
+        let parts = vec![<E as Pairing>::G2::zero(); n];
+        let dec_key = agg_dec(&parts, &ct.sa1, &ct.sa2, ct.t, n, &[true; 3], &ak, &params).await;
+        assert!(dec_key.err() == Some(Error::SizeMismatch { what: "selector entries", expected: n, got: 3 }));
+
+        let dec_key = agg_dec(&parts, &ct.sa1, &ct.sa2, ct.t, n, &[true, false, false, false], &ak, &params).await;
+        assert!(dec_key.err() == Some(Error::NotEnoughParts { t: 1, got: 0 }));
+    }
+
+    #[tokio::test]
+    async fn test_checked_partial_decryption() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let TestCommittee { params, sk, agg_key: ak, .. } = test_committee::<E>(n, &mut rng).await;
+        let (ct, proof) = encrypt_cca::<E>(&ak, 2, &params).unwrap();
+
+        let part = sk[1].checked_partial_decryption(ct.gamma_g2, &ct.sa1, &ct.sa2, ct.t, &proof, &ak, &params);
+        assert!(part == Ok(sk[1].partial_decryption(ct.gamma_g2)));
+
+        // a header built around a point the requester doesn't know the discrete log of
+        let target = <E as Pairing>::G2::rand(&mut rng);
+        let forged = sk[1].checked_partial_decryption(target, &ct.sa1, &ct.sa2, ct.t, &proof, &ak, &params);
+        assert!(forged == Err(Error::InvalidProof));
+
+        let other = encrypt::<E>(&ak, 2, &params).unwrap();
+        let forged = sk[1].checked_partial_decryption(other.gamma_g2, &other.sa1, &other.sa2, other.t, &proof, &ak, &params);
+        assert!(forged == Err(Error::InvalidProof));
+
+        // t out of range
+        let forged = sk[1].checked_partial_decryption(ct.gamma_g2, &ct.sa1, &ct.sa2, n, &proof, &ak, &params);
+        assert!(forged == Err(Error::Threshold { t: n, n }));
     }
 }
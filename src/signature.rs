@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use ark_ec::pairing::Pairing;
+use ark_ec::Group;
+use ark_serialize::*;
+use ark_std::{UniformRand, Zero};
+use rand::rngs::OsRng;
+
+use crate::{
+    curve::CurveId, decryption::aggregate_proof, encryption::{kem_header, EncryptionKey}, error::Error, hash_to_curve::DST_G2, kzg::UniversalParams, setup::AggregateKey
+};
+
+/// Key the threshold signatures of a committee are checked with. It holds the same elements as the
+/// `EncryptionKey` of the committee for the threshold, so `/getenckey` serves it too.
+pub type VerificationKey<E> = EncryptionKey<E>;
+
+/// The point the members sign for `msg`, `msg` hashed to G2 with `hash_to_curve::DST_G2`
+pub fn message_point<E: CurveId>(msg: &[u8]) -> E::G2 {
+    E::hash_to_g2(DST_G2, msg)
+}
+
+/// Checks the signature of `msg` by the member with `bls_pk`, `e(bls_pk, H(msg)) = e(g1, sig)`
+pub fn verify_share<E: CurveId>(bls_pk: &E::G1, msg: &[u8], sig: &E::G2) -> bool {
+    E::pairing(*bls_pk, message_point::<E>(msg)) == E::pairing(E::G1::generator(), *sig)
+}
+
+/// Signature of a message by at least `t` members of a committee: the aggregate of their signatures
+/// along with the hinTS proof that the signers meet the threshold, the one `agg_dec` builds. Its size
+/// doesn't depend on the committee.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ThresholdSignature<E: Pairing> {
+    /// aggregate of the selected signatures under the aggregate key of the signers
+    pub sigma: E::G2,
+    /// commitment to the polynomial B whose zeros are the slots that didn't sign
+    pub b_g2: E::G2,
+    /// -apk, -qz, -qx, qhatx, -bhat and -q0, as `agg_dec` pairs them
+    pub proof: [E::G1; 6],
+}
+
+/// Aggregates the signatures of `msg` in `signatures`, keyed by the slot of their member (1 to
+/// `n - 1`), into a signature for threshold `t`. The slots are taken in order until `t` of them are
+/// selected. Signatures aren't checked here, wrong ones should be left out with `verify_share` or
+/// `decryption::batch_part_verify` first or the result won't verify.
+pub async fn aggregate<E: CurveId>(
+    msg: &[u8],
+    signatures: &HashMap<usize, E::G2>,
+    t: usize,
+    agg_key: &AggregateKey<E>,
+    params: &UniversalParams<E>,
+) -> Result<ThresholdSignature<E>, Error> {
+    let n = agg_key.n;
+    if let Some(&id) = signatures.keys().find(|&&slot| slot == 0 || slot >= n) {
+        return Err(Error::PartyIndex { id, n });
+    }
+
+    // the dummy party's key is nullified, its signature is the message point itself
+    let mut shares = vec![message_point::<E>(msg)];
+    let mut selector = vec![true];
+    let mut selected = 0;
+    for slot in 1..n {
+        match signatures.get(&slot) {
+            Some(sig) if selected < t => {
+                selected += 1;
+                shares.push(*sig);
+                selector.push(true);
+            }
+            _ => {
+                shares.push(E::G2::zero());
+                selector.push(false);
+            }
+        }
+    }
+
+    let (proof, [b_g2, sigma]) = aggregate_proof(&shares, t, n, &selector, agg_key, params).await?;
+    Ok(ThresholdSignature { sigma, b_g2, proof })
+}
+
+/// Checks a signature of `msg` by at least `vk.t` members of the committee of `vk`. The pairing
+/// equations of the proof are the ones `encrypt` folds into a ciphertext, so they are checked at once
+/// the same way: the signature has to "decrypt" a fresh header for the message point.
+pub fn verify<E: CurveId>(vk: &VerificationKey<E>, msg: &[u8], signature: &ThresholdSignature<E>) -> bool {
+    let mut rng = OsRng;
+    let s: [E::ScalarField; 5] = std::array::from_fn(|_| E::ScalarField::rand(&mut rng));
+    let (sa1, sa2) = kem_header(vk, message_point::<E>(msg), &s);
+
+    let mut lhs = signature.proof.to_vec();
+    lhs.extend_from_slice(&sa1);
+
+    let mut rhs = sa2.to_vec();
+    rhs.extend_from_slice(&[signature.b_g2, signature.sigma]);
+
+    E::multi_pairing(lhs, rhs) == vk.e_gh() * s[4]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{test_committee, TestCommittee};
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[tokio::test]
+    async fn test_signature() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t: usize = 3;
+
+        let TestCommittee { params, sk, pk, agg_key, .. } = test_committee::<E>(n, &mut rng).await;
+        let vk = VerificationKey::new(&agg_key.without_hints(), t, &params).unwrap();
+
+        let msg = b"block 42 is final";
+        let mut signatures = HashMap::new();
+        for slot in [1, 3, 5, 6] {
+            let sig = sk[slot].sign(msg);
+            assert!(verify_share::<E>(&pk[slot].bls_pk, msg, &sig));
+            signatures.insert(slot, sig);
+        }
+        assert!(!verify_share::<E>(&pk[2].bls_pk, msg, &signatures[&1]));
+
+        let signature = aggregate(msg, &signatures, t, &agg_key, &params).await.unwrap();
+        assert!(verify(&vk, msg, &signature));
+        assert!(!verify(&vk, b"block 43 is final", &signature));
+        assert!(!verify(&VerificationKey::new(&agg_key, t + 1, &params).unwrap(), msg, &signature));
+
+        let mut forged = signature.clone();
+        forged.sigma += message_point::<E>(msg);
+        assert!(!verify(&vk, msg, &forged));
+
+        // fewer than t signers can't make a signature for t
+        signatures.remove(&5);
+        signatures.remove(&6);
+        let signature = aggregate(msg, &signatures, t, &agg_key, &params).await;
+        assert_eq!(signature.err(), Some(Error::NotEnoughParts { t, got: 2 }));
+        let signature = aggregate(msg, &signatures, 2, &agg_key, &params).await.unwrap();
+        assert!(!verify(&vk, msg, &signature));
+
+        signatures.insert(8, sk[1].sign(msg));
+        assert_eq!(aggregate(msg, &signatures, t, &agg_key, &params).await.err(), Some(Error::PartyIndex { id: 8, n }));
+    }
+}
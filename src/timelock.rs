@@ -0,0 +1,136 @@
+use ark_ec::{
+    pairing::{Pairing, PairingOutput},
+    Group,
+};
+use ark_serialize::*;
+use ark_std::UniformRand;
+use rand::{rngs::OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    curve::CurveId, encryption::{encrypt_with_key, Ciphertext, EncryptionKey}, error::Error, kzg::UniversalParams, setup::AggregateKey
+};
+
+/// DST the rounds are hashed with, the one of drand's unchained scheme (`pedersen-bls-unchained`)
+pub const BEACON_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+
+/// The point a beacon signs for `round`: SHA-256 of the round as 8 big endian bytes, hashed to G2.
+/// On BLS12-381 this is the message of drand's unchained beacons, whose signatures open timelocks.
+pub fn round_point<E: CurveId>(round: u64) -> E::G2 {
+    E::hash_to_g2(BEACON_DST, &Sha256::digest(round.to_be_bytes()))
+}
+
+/// Checks the signature of `round` of the beacon with public key `beacon_pk`, `e(g, sig) = e(pk, H(round))`
+pub fn verify_round<E: CurveId>(beacon_pk: &E::G1, round: u64, sig: &E::G2) -> bool {
+    E::pairing(E::G1::generator(), *sig) == E::pairing(*beacon_pk, round_point::<E>(round))
+}
+
+/// Local stand-in for a BLS randomness beacon, which signs every round with a single key. A real
+/// beacon only publishes the signature of a round once it is reached.
+pub struct Beacon<E: Pairing> {
+    sk: E::ScalarField,
+    pub pk: E::G1,
+}
+
+impl<E: CurveId> Beacon<E> {
+    pub fn new<R: RngCore>(rng: &mut R) -> Self {
+        let sk = E::ScalarField::rand(rng);
+        Beacon { sk, pk: E::G1::generator() * sk }
+    }
+
+    pub fn sign(&self, round: u64) -> E::G2 {
+        round_point::<E>(round) * self.sk
+    }
+}
+
+/// The key of a `Ciphertext` locked to a round of a beacon, Boneh-Franklin style as tlock does:
+/// `u = g^r` and `v = enc_key * e(pk, H(round))^r`. Once the beacon signs the round, `e(u, sig)`
+/// unmasks `enc_key` and the ciphertext decrypts without the committee.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Timelock<E: Pairing> {
+    pub round: u64,
+    pub u: E::G1,
+    pub v: PairingOutput<E>,
+}
+
+impl<E: CurveId> Timelock<E> {
+    /// Locks the key of `ct` to `round` of the beacon with public key `beacon_pk`
+    pub fn new(ct: &Ciphertext<E>, beacon_pk: &E::G1, round: u64) -> Self {
+        let r = E::ScalarField::rand(&mut OsRng);
+        let mask = E::pairing(*beacon_pk, round_point::<E>(round)) * r;
+
+        Timelock {
+            round,
+            u: E::G1::generator() * r,
+            v: ct.enc_key + mask,
+        }
+    }
+
+    /// The `enc_key` of the locked ciphertext from the beacon signature of `round`, `InvalidBeacon` if
+    /// the signature doesn't verify under `beacon_pk`
+    pub fn open(&self, beacon_pk: &E::G1, sig: &E::G2) -> Result<PairingOutput<E>, Error> {
+        if !verify_round::<E>(beacon_pk, self.round, sig) {
+            return Err(Error::InvalidBeacon(self.round));
+        }
+
+        Ok(self.v - E::pairing(self.u, *sig))
+    }
+}
+
+/// `encrypt` whose key can also be recovered from the signature of `round` of the beacon with public
+/// key `beacon_pk`: `agg_dec` of `t` partial decryptions and `Timelock::open` give the same `enc_key`,
+/// which the dem uses as usual.
+pub fn encrypt_timelock<E: CurveId>(
+    apk: &AggregateKey<E>,
+    t: usize,
+    params: &UniversalParams<E>,
+    beacon_pk: &E::G1,
+    round: u64,
+) -> Result<(Ciphertext<E>, Timelock<E>), Error> {
+    Ok(encrypt_timelock_with_key(&EncryptionKey::new(apk, t, params)?, beacon_pk, round))
+}
+
+/// `encrypt_timelock` with the threshold of `ek`
+pub fn encrypt_timelock_with_key<E: CurveId>(ek: &EncryptionKey<E>, beacon_pk: &E::G1, round: u64) -> (Ciphertext<E>, Timelock<E>) {
+    let ct = encrypt_with_key(ek);
+    let timelock = Timelock::new(&ct, beacon_pk, round);
+    (ct, timelock)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decryption::agg_dec, dem::Dem, utils::{test_committee, TestCommittee}
+    };
+
+    type E = ark_bls12_381::Bls12_381;
+    type G2 = <E as Pairing>::G2;
+
+    #[tokio::test]
+    async fn test_timelock() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let TestCommittee { params, sk, agg_key: ak, .. } = test_committee::<E>(n, &mut rng).await;
+
+        let beacon = Beacon::<E>::new(&mut rng);
+        let (ct, timelock) = encrypt_timelock(&ak, 1, &params, &beacon.pk, 1000).unwrap();
+        let dem = Dem::Aes256Gcm;
+        let (nonce, payload) = dem.encrypt(&dem.derive_key(&ct.enc_key), b"Hello, world!", &ct.header(), &mut rng).unwrap();
+
+        // the committee path
+        let parts: Vec<G2> = sk.iter().map(|sk| sk.partial_decryption(ct.gamma_g2)).collect();
+        let dec_key = agg_dec(&parts, &ct.sa1, &ct.sa2, ct.t, n, &[true, true, false, false], &ak, &params).await.unwrap();
+        assert!(dec_key == ct.enc_key);
+
+        // the beacon path
+        let sig = beacon.sign(1000);
+        assert!(verify_round::<E>(&beacon.pk, 1000, &sig));
+        let dec_key = timelock.open(&beacon.pk, &sig).unwrap();
+        let msg = dem.decrypt(&dem.derive_key(&dec_key), &nonce, &payload, &ct.header()).unwrap();
+        assert_eq!(msg, b"Hello, world!");
+
+        assert_eq!(timelock.open(&beacon.pk, &beacon.sign(999)), Err(Error::InvalidBeacon(1000)));
+        assert_eq!(timelock.open(&Beacon::<E>::new(&mut rng).pk, &sig), Err(Error::InvalidBeacon(1000)));
+    }
+}
@@ -1,4 +1,5 @@
-use ark_ec::{bls12::Bls12, pairing::Pairing};
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
 use ark_ff::{FftField, Field};
 use ark_poly::{
     univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Evaluations, Polynomial,
@@ -6,9 +7,11 @@ use ark_poly::{
 };
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::Zero;
-use std::{ops::{Mul, Sub}, sync::Once, time};
+use std::{future::Future, ops::{Mul, Sub}, sync::Arc, time};
+#[cfg(not(feature = "server"))]
+use std::{convert::Infallible, pin::Pin};
 
-use crate::{api::types::{E, G1, G2}, kzg::{UniversalParams, KZG10}, setup::SecretKey};
+use crate::{error::{check_n, Error}, kzg::{check_degree_is_too_large, UniversalParams, KZG10}, setup::SecretKey};
 
 // 1 at omega^i and 0 elsewhere on domain {omega^i}_{i \in [n]}
 pub fn lagrange_poly<F: FftField>(n: usize, i: usize) -> DensePolynomial<F> {
@@ -27,6 +30,31 @@ pub fn lagrange_poly<F: FftField>(n: usize, i: usize) -> DensePolynomial<F> {
     eval_form.interpolate()
 }
 
+/// size of the evaluation domain for `n` parties, the next power of two. The slots from `n` on are
+/// taken by nullified dummy parties, see `setup::pad_pks`
+pub fn domain_size(n: usize) -> usize {
+    n.next_power_of_two()
+}
+
+/// Runs `future` on a tokio task with the `server` feature. Without it there is no runtime to spawn
+/// on and `future` runs when it is awaited, so the async functions of the library work on any executor.
+#[cfg(feature = "server")]
+pub(crate) fn spawn<F>(future: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(future)
+}
+
+#[cfg(not(feature = "server"))]
+pub(crate) fn spawn<F>(future: F) -> Pin<Box<dyn Future<Output = Result<F::Output, Infallible>>>>
+where
+    F: Future + 'static,
+{
+    Box::pin(async move { Ok(future.await) })
+}
+
 /// interpolates a polynomial when all evaluations except at points[0] are zero
 /// todo: check that multiplication is fast as one polynomial is shorter
 pub fn interp_mostly_zero<F: Field>(eval: F, points: &Vec<F>) -> DensePolynomial<F> {
@@ -50,23 +78,31 @@ pub fn interp_mostly_zero<F: Field>(eval: F, points: &Vec<F>) -> DensePolynomial
 }
 
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
-pub struct IsValidHelper {
-    pub li: Vec<G2>,
-    pub li_minus0: Vec<G2>,
-    pub li_by_tau: Vec<G2>,
-    pub li_by_z: Vec<Vec<G2>>
+pub struct IsValidHelper<E: Pairing = Bls12_381> {
+    pub li: Vec<E::G2>,
+    pub li_minus0: Vec<E::G2>,
+    pub li_by_tau: Vec<E::G2>,
+    pub li_by_z: Vec<Vec<E::G2>>
 }
 
-impl IsValidHelper {
-    pub async fn new(n: usize) -> Self {
-        let domain = Radix2EvaluationDomain::<<Bls12<ark_bls12_381::Config> as Pairing>::ScalarField>::new(n).unwrap();
+impl<E: Pairing> IsValidHelper<E> {
+    pub async fn new(n: usize, params: &UniversalParams<E>) -> Result<Self, Error> {
+        check_n(n)?;
+        check_degree_is_too_large(n - 1, params.powers_of_h.len())?;
+
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(n).unwrap();
+
+        let lagrange_polys = lagrange_polys::<E>(n);
+        let params = Arc::new(params.to_owned());
 
         let mut tasks = Vec::new();
         let mut li_by_z_times_li_tasks = Vec::new();
 
         for id in 0..n {
-            let li_task = tokio::spawn(async move {
-                let li: G2 = KZG10::commit_g2(&get_kzg_setup(), &get_lagrange_polys()[id].to_owned())
+            let lagrange_polys_async = lagrange_polys.clone();
+            let params_async = params.clone();
+            let li_task = spawn(async move {
+                let li: E::G2 = KZG10::commit_g2(&params_async, &lagrange_polys_async[id].to_owned())
                     .expect("commitment failed")
                     .into();
                 
@@ -74,10 +110,12 @@ impl IsValidHelper {
             });
             tasks.push(li_task);
 
-            let li_minus0_task = tokio::spawn(async move {
-                let mut f = get_lagrange_polys()[id].to_owned();
-                f.coeffs[0] = <Bls12<ark_bls12_381::Config> as Pairing>::ScalarField::zero();
-                let li_minus0 = KZG10::commit_g2(&get_kzg_setup(), &f)
+            let lagrange_polys_async = lagrange_polys.clone();
+            let params_async = params.clone();
+            let li_minus0_task = spawn(async move {
+                let mut f = lagrange_polys_async[id].to_owned();
+                f.coeffs[0] = E::ScalarField::zero();
+                let li_minus0 = KZG10::commit_g2(&params_async, &f)
                     .expect("commitment failed")
                     .into();
 
@@ -85,9 +123,11 @@ impl IsValidHelper {
             });
             tasks.push(li_minus0_task);
 
-            let li_by_tau_task = tokio::spawn(async move {
-                let f: DensePolynomial<<E as Pairing>::ScalarField> = DensePolynomial::from_coefficients_vec(get_lagrange_polys()[id].coeffs[1..].to_vec());
-                let li_by_tau_task = KZG10::commit_g2(&get_kzg_setup(), &f)
+            let lagrange_polys_async = lagrange_polys.clone();
+            let params_async = params.clone();
+            let li_by_tau_task = spawn(async move {
+                let f: DensePolynomial<E::ScalarField> = DensePolynomial::from_coefficients_vec(lagrange_polys_async[id].coeffs[1..].to_vec());
+                let li_by_tau_task = KZG10::commit_g2(&params_async, &f)
                     .expect("commitment failed")
                     .into();
 
@@ -97,14 +137,16 @@ impl IsValidHelper {
 
             // let c = sk.clone();
             for j in 0..n {
+                let lagrange_polys_async = lagrange_polys.clone();
+                let params_async = params.clone();
                 if j == id {
-                    let li_by_z_task = tokio::spawn(async move {
-                        let li = &get_lagrange_polys()[id];
+                    let li_by_z_task = spawn(async move {
+                        let li = &lagrange_polys_async[id];
                         let l = li.mul(li).sub(li);
         
                         let f = l.divide_by_vanishing_poly(domain).unwrap().0;
         
-                        let li_by_z = KZG10::commit_g2(&get_kzg_setup(), &f)
+                        let li_by_z = KZG10::commit_g2(&params_async, &f)
                             .expect("commitment failed")
                             .into();
         
@@ -114,14 +156,14 @@ impl IsValidHelper {
                     continue;
                 }
 
-                let li_by_z_times_li_task = tokio::spawn(async move {
-                    let li = &get_lagrange_polys()[id];
-                    let li_j = &get_lagrange_polys()[j];
+                let li_by_z_times_li_task = spawn(async move {
+                    let li = &lagrange_polys_async[id];
+                    let li_j = &lagrange_polys_async[j];
                     let l = li_j.mul(li);
 
                     let f = l.divide_by_vanishing_poly(domain).unwrap().0;
 
-                    let li_by_z_times_li = KZG10::commit_g2(&get_kzg_setup(), &f)
+                    let li_by_z_times_li = KZG10::commit_g2(&params_async, &f)
                         .expect("commitment failed")
                         .into();
 
@@ -165,69 +207,41 @@ impl IsValidHelper {
         li_by_z_times_li.push(tmp.to_owned());
         ret.li_by_z = li_by_z_times_li;
         
-        ret
-    }
-}
-
-static mut LAGRANGE_POLYS: Option<&'static [DensePolynomial<<Bls12<ark_bls12_381::Config> as Pairing>::ScalarField>]> = None;
-static LAGRANGE_INIT: Once = Once::new();
-
-static mut KZG_SETUP: Option<UniversalParams<E>> = None;
-static KZG_INIT: Once = Once::new();
-
-fn initialize_lagrange_polys(n: usize) {
-    let polys: Vec<DensePolynomial<<Bls12<ark_bls12_381::Config> as Pairing>::ScalarField>> = (0..n)
-        .map(|j| lagrange_poly(n, j))
-        .collect();
-    unsafe {
-        LAGRANGE_POLYS = Some(Box::leak(polys.into_boxed_slice()));
-    }
-}
-
-fn get_lagrange_polys() -> &'static [DensePolynomial<<Bls12<ark_bls12_381::Config> as Pairing>::ScalarField>] {
-    unsafe {
-        LAGRANGE_POLYS.expect("LAGRANGE_POLYS has not been initialized")
+        Ok(ret)
     }
 }
 
-fn initialize_kzg_setup(kzg_setup: UniversalParams<E>) {
-    unsafe {
-        KZG_SETUP = Some(kzg_setup);
-    }
-}
-
-fn get_kzg_setup() -> &'static UniversalParams<E> {
-    unsafe {
-        KZG_SETUP.as_ref().expect("KZG_SETUP has not been initialized")
-    }
+/// lagrange polynomials of the domain of size `n`, shared between the tasks of a helper
+fn lagrange_polys<E: Pairing>(n: usize) -> Arc<Vec<DensePolynomial<E::ScalarField>>> {
+    Arc::new((0..n).map(|j| lagrange_poly(n, j)).collect())
 }
 
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone)]
-pub struct LagrangePolyHelper {
-    pub li: Vec<G1>,
-    pub li_minus0: Vec<G1>,
-    pub li_by_tau: Vec<G1>,
-    pub li_by_z: Vec<Vec<G1>>,
+pub struct LagrangePolyHelper<E: Pairing = Bls12_381> {
+    pub li: Vec<E::G1>,
+    pub li_minus0: Vec<E::G1>,
+    pub li_by_tau: Vec<E::G1>,
+    pub li_by_z: Vec<Vec<E::G1>>,
 }
 
-impl LagrangePolyHelper {
-    pub async fn new(_sk: &SecretKey<E>, n: usize, params: &UniversalParams<E>) -> Self {
-        let domain = Radix2EvaluationDomain::<<Bls12<ark_bls12_381::Config> as Pairing>::ScalarField>::new(n).unwrap();
+impl<E: Pairing> LagrangePolyHelper<E> {
+    pub async fn new(_sk: &SecretKey<E>, n: usize, params: &UniversalParams<E>) -> Result<Self, Error> {
+        check_n(n)?;
+        check_degree_is_too_large(n - 1, params.powers_of_g.len())?;
 
-        KZG_INIT.call_once(|| {
-            initialize_kzg_setup(params.to_owned());
-        });
+        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(n).unwrap();
 
-        LAGRANGE_INIT.call_once(|| {
-            initialize_lagrange_polys(n);
-        });
+        let lagrange_polys = lagrange_polys::<E>(n);
+        let params = Arc::new(params.to_owned());
 
         let mut tasks = Vec::new();
         let mut li_by_z_times_li_tasks = Vec::new();
 
         for id in 0..n {
-            let li_task = tokio::spawn(async move {
-                let li: G1 = KZG10::commit_g1(&get_kzg_setup(), &get_lagrange_polys()[id].to_owned())
+            let lagrange_polys_async = lagrange_polys.clone();
+            let params_async = params.clone();
+            let li_task = spawn(async move {
+                let li: E::G1 = KZG10::commit_g1(&params_async, &lagrange_polys_async[id].to_owned())
                     .expect("commitment failed")
                     .into();
                 
@@ -235,10 +249,12 @@ impl LagrangePolyHelper {
             });
             tasks.push(li_task);
 
-            let li_minus0_task = tokio::spawn(async move {
-                let mut f = get_lagrange_polys()[id].to_owned();
-                f.coeffs[0] = <Bls12<ark_bls12_381::Config> as Pairing>::ScalarField::zero();
-                let li_minus0 = KZG10::commit_g1(&get_kzg_setup(), &f)
+            let lagrange_polys_async = lagrange_polys.clone();
+            let params_async = params.clone();
+            let li_minus0_task = spawn(async move {
+                let mut f = lagrange_polys_async[id].to_owned();
+                f.coeffs[0] = E::ScalarField::zero();
+                let li_minus0 = KZG10::commit_g1(&params_async, &f)
                     .expect("commitment failed")
                     .into();
 
@@ -246,9 +262,11 @@ impl LagrangePolyHelper {
             });
             tasks.push(li_minus0_task);
 
-            let li_by_tau_task = tokio::spawn(async move {
-                let f: DensePolynomial<<E as Pairing>::ScalarField> = DensePolynomial::from_coefficients_vec(get_lagrange_polys()[id].coeffs[1..].to_vec());
-                let li_by_tau_task = KZG10::commit_g1(&get_kzg_setup(), &f)
+            let lagrange_polys_async = lagrange_polys.clone();
+            let params_async = params.clone();
+            let li_by_tau_task = spawn(async move {
+                let f: DensePolynomial<E::ScalarField> = DensePolynomial::from_coefficients_vec(lagrange_polys_async[id].coeffs[1..].to_vec());
+                let li_by_tau_task = KZG10::commit_g1(&params_async, &f)
                     .expect("commitment failed")
                     .into();
 
@@ -258,14 +276,16 @@ impl LagrangePolyHelper {
 
             // let c = sk.clone();
             for j in 0..n {
+                let lagrange_polys_async = lagrange_polys.clone();
+                let params_async = params.clone();
                 if j == id {
-                    let li_by_z_task = tokio::spawn(async move {
-                        let li = &get_lagrange_polys()[id];
+                    let li_by_z_task = spawn(async move {
+                        let li = &lagrange_polys_async[id];
                         let l = li.mul(li).sub(li);
         
                         let f = l.divide_by_vanishing_poly(domain).unwrap().0;
         
-                        let li_by_z = KZG10::commit_g1(&get_kzg_setup(), &f)
+                        let li_by_z = KZG10::commit_g1(&params_async, &f)
                             .expect("commitment failed")
                             .into();
         
@@ -275,14 +295,14 @@ impl LagrangePolyHelper {
                     continue;
                 }
 
-                let li_by_z_times_li_task = tokio::spawn(async move {
-                    let li = &get_lagrange_polys()[id];
-                    let li_j = &get_lagrange_polys()[j];
+                let li_by_z_times_li_task = spawn(async move {
+                    let li = &lagrange_polys_async[id];
+                    let li_j = &lagrange_polys_async[j];
                     let l = li_j.mul(li);
 
                     let f = l.divide_by_vanishing_poly(domain).unwrap().0;
 
-                    let li_by_z_times_li = KZG10::commit_g1(&get_kzg_setup(), &f)
+                    let li_by_z_times_li = KZG10::commit_g1(&params_async, &f)
                         .expect("commitment failed")
                         .into();
 
@@ -326,6 +346,34 @@ impl LagrangePolyHelper {
         li_by_z_times_li.push(tmp.to_owned());
         ret.li_by_z = li_by_z_times_li;
         
-        ret
+        Ok(ret)
     }
-}
\ No newline at end of file
+}
+
+/// A committee of `n` parties for the tests, padded to `domain_size(n)` slots: the transcript, the
+/// lagrange helper, the secret keys with the dummy party's one at slot 0 nullified, the public keys
+/// of the `n` parties and the aggregate key
+#[cfg(test)]
+pub(crate) struct TestCommittee<E: Pairing> {
+    pub params: UniversalParams<E>,
+    pub lagrange_helper: LagrangePolyHelper<E>,
+    pub sk: Vec<SecretKey<E>>,
+    pub pk: Vec<crate::setup::PublicKey<E>>,
+    pub agg_key: crate::setup::AggregateKey<E>,
+}
+
+#[cfg(test)]
+pub(crate) async fn test_committee<E: Pairing>(n: usize, rng: &mut impl ark_std::rand::RngCore) -> TestCommittee<E> {
+    use crate::setup::{get_pk_exp, pad_pks, AggregateKey};
+
+    let size = domain_size(n);
+    let params = KZG10::<E, DensePolynomial<E::ScalarField>>::setup(size, rng).unwrap();
+    let lagrange_helper = LagrangePolyHelper::<E>::new(&SecretKey::new(rng), size, &params).await.unwrap();
+
+    let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::<E>::new(rng)).collect();
+    sk[0].nullify();
+    let pk: Vec<_> = (0..n).map(|i| get_pk_exp(&sk[i], i, size, &lagrange_helper).unwrap()).collect();
+    let agg_key = AggregateKey::<E>::new(pad_pks(pk.clone(), &lagrange_helper).unwrap(), n, &params).unwrap();
+
+    TestCommittee { params, lagrange_helper, sk, pk, agg_key }
+}
@@ -0,0 +1,198 @@
+use std::{collections::HashMap, ops::Range};
+
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_std::{One, Zero};
+
+use crate::{
+    decryption::agg_dec, error::{check_parties, Error}, kzg::UniversalParams, setup::{get_pk_exp, pad_pks, AggregateKey, PublicKey, SecretKey}, utils::{domain_size, LagrangePolyHelper}
+};
+
+/// A committee given by the weight of each party. Party `i` takes `weights[i]` consecutive slots
+/// after the dummy party at slot 0 and signs all of them with a single key, so thresholds are weights:
+/// `encrypt` and `agg_dec` work on slots and a ciphertext for `t` needs parties of total weight `t`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeightedCommittee {
+    weights: Vec<usize>,
+    // first slot of every party
+    offsets: Vec<usize>,
+}
+
+impl WeightedCommittee {
+    pub fn new(weights: Vec<usize>) -> Result<Self, Error> {
+        if let Some(party) = weights.iter().position(|&weight| weight == 0) {
+            return Err(Error::ZeroWeight(party));
+        }
+        check_parties(weights.len() + 1)?;
+
+        let mut offsets = Vec::new();
+        let mut slot = 1;
+        for weight in weights.iter() {
+            offsets.push(slot);
+            slot += weight;
+        }
+
+        Ok(WeightedCommittee { weights, offsets })
+    }
+
+    pub fn weights(&self) -> &[usize] {
+        &self.weights
+    }
+
+    pub fn total_weight(&self) -> usize {
+        self.weights.iter().sum()
+    }
+
+    /// number of slots the committee takes, the dummy party included, to use as `n`
+    pub fn n(&self) -> usize {
+        self.total_weight() + 1
+    }
+
+    /// slots of `party`, `PartyIndex` if there is no such party
+    pub fn slots(&self, party: usize) -> Result<Range<usize>, Error> {
+        match self.offsets.get(party) {
+            Some(&offset) => Ok(offset..offset + self.weights[party]),
+            None => Err(Error::PartyIndex { id: party, n: self.weights.len() }),
+        }
+    }
+
+    /// the party holding `slot`, `None` for the dummy party and slots past the committee
+    pub fn party(&self, slot: usize) -> Option<usize> {
+        if slot == 0 || slot >= self.n() {
+            return None;
+        }
+        Some(self.offsets.partition_point(|&offset| offset <= slot) - 1)
+    }
+
+    /// The keys of `party` for each of its slots, from a lagrange helper of `domain_size(self.n())`
+    pub fn get_pks<E: Pairing>(&self, sk: &SecretKey<E>, party: usize, lagrange_polys: &LagrangePolyHelper<E>) -> Result<Vec<PublicKey<E>>, Error> {
+        let size = domain_size(self.n());
+        self.slots(party)?
+            .map(|slot| get_pk_exp(sk, slot, size, lagrange_polys))
+            .collect()
+    }
+
+    /// Slot-ordered keys of the committee from the keys of each party, the dummy party and the
+    /// padding included, as `AggregateKey::new` takes them
+    pub fn slot_pks<E: Pairing>(&self, pks: &[Vec<PublicKey<E>>], lagrange_polys: &LagrangePolyHelper<E>) -> Result<Vec<PublicKey<E>>, Error> {
+        if pks.len() != self.weights.len() {
+            return Err(Error::SizeMismatch { what: "parties", expected: self.weights.len(), got: pks.len() });
+        }
+
+        let dummy = SecretKey { sk: E::ScalarField::one() };
+        let mut slot_pks = vec![get_pk_exp(&dummy, 0, domain_size(self.n()), lagrange_polys)?];
+        for (party, party_pks) in pks.iter().enumerate() {
+            if party_pks.len() != self.weights[party] {
+                return Err(Error::SizeMismatch { what: "slot keys", expected: self.weights[party], got: party_pks.len() });
+            }
+            slot_pks.extend_from_slice(party_pks);
+        }
+
+        pad_pks(slot_pks, lagrange_polys)
+    }
+
+    pub fn aggregate_key<E: Pairing>(
+        &self,
+        pks: &[Vec<PublicKey<E>>],
+        lagrange_polys: &LagrangePolyHelper<E>,
+        params: &UniversalParams<E>,
+    ) -> Result<AggregateKey<E>, Error> {
+        AggregateKey::new(self.slot_pks(pks, lagrange_polys)?, self.n(), params)
+    }
+
+    /// Copies the partial decryption of each party in `parts` to all of its slots
+    pub fn expand_parts<T: Clone>(&self, parts: &HashMap<usize, T>) -> Result<HashMap<usize, T>, Error> {
+        let mut slot_parts = HashMap::new();
+        for (&party, part) in parts {
+            for slot in self.slots(party)? {
+                slot_parts.insert(slot, part.clone());
+            }
+        }
+
+        Ok(slot_parts)
+    }
+}
+
+/// `agg_dec` for a weighted committee, `parts` maps a party to its partial decryption of `gamma_g2`.
+/// The slots of the parties are taken in order until `t` of them are selected, so a heavy party may
+/// only be used for part of its weight.
+#[allow(clippy::too_many_arguments)]
+pub async fn agg_dec_weighted<E: Pairing>(
+    committee: &WeightedCommittee,
+    parts: &HashMap<usize, E::G2>,
+    gamma_g2: &E::G2,
+    sa1: &[E::G1; 2],
+    sa2: &[E::G2; 6],
+    t: usize,
+    agg_key: &AggregateKey<E>,
+    params: &UniversalParams<E>,
+) -> Result<PairingOutput<E>, Error> {
+    let slot_parts = committee.expand_parts(parts)?;
+
+    // the dummy party's key is nullified, its partial decryption is gamma_g2 itself
+    let mut partial_decryptions = vec![*gamma_g2];
+    let mut selector = vec![true];
+    let mut selected = 0;
+    for slot in 1..committee.n() {
+        match slot_parts.get(&slot) {
+            Some(part) if selected < t => {
+                selected += 1;
+                partial_decryptions.push(*part);
+                selector.push(true);
+            }
+            _ => {
+                partial_decryptions.push(E::G2::zero());
+                selector.push(false);
+            }
+        }
+    }
+
+    agg_dec(&partial_decryptions, sa1, sa2, t, committee.n(), &selector, agg_key, params).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{encryption::encrypt, utils::{test_committee, TestCommittee}};
+
+    type E = ark_bls12_381::Bls12_381;
+
+    #[tokio::test]
+    async fn test_weighted() {
+        let mut rng = ark_std::test_rng();
+        let committee = WeightedCommittee::new(vec![3, 1, 2]).unwrap();
+        assert_eq!(committee.n(), 7);
+        assert_eq!(committee.slots(2), Ok(5..7));
+        assert_eq!(committee.party(3), Some(0));
+        assert_eq!(committee.party(4), Some(1));
+        assert_eq!(committee.party(0), None);
+        assert_eq!(committee.party(7), None);
+        assert_eq!(WeightedCommittee::new(vec![1, 0]), Err(Error::ZeroWeight(1)));
+
+        let TestCommittee { params, lagrange_helper, .. } = test_committee::<E>(committee.n(), &mut rng).await;
+
+        let sk: Vec<SecretKey<E>> = (0..3).map(|_| SecretKey::new(&mut rng)).collect();
+        let pks: Vec<Vec<PublicKey<E>>> = (0..3)
+            .map(|party| committee.get_pks(&sk[party], party, &lagrange_helper).unwrap())
+            .collect();
+        let agg_key = committee.aggregate_key(&pks, &lagrange_helper, &params).unwrap();
+
+        let t = 4;
+        let ct = encrypt::<E>(&agg_key, t, &params).unwrap();
+
+        // parties 0 and 1 weigh 4 together, party 2 alone doesn't reach t
+        let mut parts = HashMap::new();
+        parts.insert(0, sk[0].partial_decryption(ct.gamma_g2));
+        parts.insert(1, sk[1].partial_decryption(ct.gamma_g2));
+        let dec_key = agg_dec_weighted(&committee, &parts, &ct.gamma_g2, &ct.sa1, &ct.sa2, t, &agg_key, &params).await;
+        assert!(dec_key == Ok(ct.enc_key));
+
+        let mut parts = HashMap::new();
+        parts.insert(2, sk[2].partial_decryption(ct.gamma_g2));
+        let dec_key = agg_dec_weighted(&committee, &parts, &ct.gamma_g2, &ct.sa1, &ct.sa2, t, &agg_key, &params).await;
+        assert!(dec_key.err() == Some(Error::NotEnoughParts { t, got: 2 }));
+
+        parts.insert(3, sk[2].partial_decryption(ct.gamma_g2));
+        let dec_key = agg_dec_weighted(&committee, &parts, &ct.gamma_g2, &ct.sa1, &ct.sa2, t, &agg_key, &params).await;
+        assert!(dec_key.err() == Some(Error::PartyIndex { id: 3, n: 3 }));
+    }
+}
@@ -11,194 +11,138 @@ import (
 	"google.golang.org/protobuf/proto"
 )
 
-func EncryptTransaction(msg []byte, pks [][]byte, t uint64, n uint64, url string) (api.EncryptResponse, error) {
+// AES256_GCM, see the data encapsulation modes of the README
+const demAes256Gcm uint32 = 1
+
+// Post sends req to url and reads the response into resp, or the ErrorResponse into the error
+func Post(url string, req proto.Message, resp proto.Message) error {
 	client := http.Client{}
-	var encryptDataResp api.EncryptResponse
 
-	req := &api.EncryptRequest{
-		Msg: msg,
-		Pks: pks,
-		T:   t,
-		N:   n,
-	}
 	data, err := proto.Marshal(req)
 	if err != nil {
-		return encryptDataResp, err
+		return err
 	}
 
 	postReader := bytes.NewReader(data)
 
-	resp, err := client.Post(url, "application/protobuf", postReader)
-
+	httpResp, err := client.Post(url, "application/protobuf", postReader)
 	if err != nil {
-		return encryptDataResp, err
-	}
-
-	if resp.StatusCode == 400 {
-		return encryptDataResp, err
+		return err
 	}
-	defer resp.Body.Close()
+	defer httpResp.Body.Close()
 
-	bodyBytes, err := io.ReadAll(resp.Body)
+	bodyBytes, err := io.ReadAll(httpResp.Body)
 	if err != nil {
-		return encryptDataResp, err
+		return err
 	}
 
-	err = proto.Unmarshal(bodyBytes, &encryptDataResp)
-	if err != nil {
-		return encryptDataResp, err
+	if httpResp.StatusCode != http.StatusOK {
+		var errResp api.ErrorResponse
+		if proto.Unmarshal(bodyBytes, &errResp) != nil {
+			return fmt.Errorf("%s: status %d", url, httpResp.StatusCode)
+		}
+		return fmt.Errorf("%s: status %d, error %d: %s", url, httpResp.StatusCode, errResp.Code, errResp.Message)
 	}
 
-	return encryptDataResp, nil
+	return proto.Unmarshal(bodyBytes, resp)
 }
 
-func DecryptTransaction(enc []byte, pks [][]byte, parts map[uint64]([]byte), gamma_g2 []byte, sa1 []byte, sa2 []byte, iv []byte, t uint64, n uint64) ([]byte, error) {
-	client := http.Client{}
-
-	req := &api.DecryptRequest{
-		Enc:     []byte(enc),
-		Pks:     pks,
-		Parts:   parts,
-		GammaG2: gamma_g2,
-		Sa1:     sa1,
-		Sa2:     sa2,
-		Iv:      iv,
-		T:       t,
-		N:       n,
-	}
-	data, err := proto.Marshal(req)
-	if err != nil {
-		return nil, err
+func RegisterCommittee(id string, pks [][]byte, n uint64, url string) (*api.RegisterResponse, error) {
+	req := &api.RegisterRequest{
+		Id:  id,
+		N:   n,
+		Pks: pks,
 	}
 
-	postReader := bytes.NewReader(data)
-
-	resp, err := client.Post("http://127.0.0.1:8080/decrypt", "application/protobuf", postReader)
-
+	var registerResp api.RegisterResponse
+	err := Post(url, req, &registerResp)
 	if err != nil {
 		return nil, err
 	}
-	if resp.StatusCode != http.StatusOK {
-		return nil, err
-	}
-	defer resp.Body.Close()
 
-	bodyBytes, err := io.ReadAll(resp.Body)
-	if err != nil {
-		return nil, err
+	return &registerResp, nil
+}
+
+func EncryptTransaction(msg []byte, committeeId string, t uint64, url string) (*api.EncryptResponse, error) {
+	req := &api.EncryptRequest{
+		Msg:         msg,
+		T:           t,
+		Dem:         demAes256Gcm,
+		Envelope:    true,
+		Cca:         true,
+		CommitteeId: committeeId,
 	}
 
-	var decryptDataResp api.Response
-	err = proto.Unmarshal(bodyBytes, &decryptDataResp)
+	var encryptDataResp api.EncryptResponse
+	err := Post(url, req, &encryptDataResp)
 	if err != nil {
 		return nil, err
 	}
 
-	return decryptDataResp.Result, nil
+	return &encryptDataResp, nil
 }
 
-func PartialDecrypt(gammaG2 []byte, url string) ([]byte, error) {
-	client := http.Client{}
-
-	req := &api.PartDecRequest{
-		GammaG2: []byte(gammaG2),
-	}
-	data, err := proto.Marshal(req)
-	if err != nil {
-		fmt.Println(err)
-		return nil, err
+func DecryptTransaction(envelope []byte, committeeId string, parts map[uint64]([]byte), url string) (*api.DecryptResponse, error) {
+	req := &api.DecryptRequest{
+		Parts:       parts,
+		Envelope:    envelope,
+		CommitteeId: committeeId,
 	}
 
-	postReader := bytes.NewReader(data)
-
-	resp, err := client.Post(url, "application/protobuf", postReader)
+	var decryptDataResp api.DecryptResponse
+	err := Post(url, req, &decryptDataResp)
 	if err != nil {
-		fmt.Println(err)
-		return nil, err
-	}
-	if resp.StatusCode != 200 {
-		fmt.Println(err)
 		return nil, err
 	}
-	defer resp.Body.Close()
 
-	bodyBytes, err := io.ReadAll(resp.Body)
-	if err != nil {
-		fmt.Println(err)
-		return nil, err
+	return &decryptDataResp, nil
+}
+
+func PartialDecrypt(enc *api.EncryptResponse, committeeId string, t uint64, url string) (*api.PartDecResponse, error) {
+	req := &api.PartDecRequest{
+		GammaG2:     enc.GammaG2,
+		Sa1:         enc.Sa1,
+		Sa2:         enc.Sa2,
+		T:           t,
+		Proof:       enc.Proof,
+		Prove:       true,
+		CommitteeId: committeeId,
 	}
 
-	var partDecResp api.Response
-	err = proto.Unmarshal(bodyBytes, &partDecResp)
+	var partDecResp api.PartDecResponse
+	err := Post(url, req, &partDecResp)
 	if err != nil {
-		fmt.Println(err)
 		return nil, err
 	}
 
-	return partDecResp.Result, nil
+	return &partDecResp, nil
 }
 
 func GetPK(id uint64, n uint64, url string) ([]byte, error) {
-	client := http.Client{}
-
 	req := &api.PKRequest{
 		Id: id,
 		N:  n,
 	}
-	data, err := proto.Marshal(req)
-	if err != nil {
-		return nil, err
-	}
 
-	postReader := bytes.NewReader(data)
-
-	resp, err := client.Post(url, "application/protobuf", postReader)
-	if err != nil {
-		return nil, err
-	}
-	defer resp.Body.Close()
-
-	bodyBytes, err := io.ReadAll(resp.Body)
+	var pkResp api.Response
+	err := Post(url, req, &pkResp)
 	if err != nil {
-		fmt.Println(err)
 		return nil, err
 	}
 
-	var partDecResp api.Response
-	err = proto.Unmarshal(bodyBytes, &partDecResp)
-	if err != nil {
-		fmt.Println(err)
-		return nil, err
-	}
-
-	return partDecResp.Result, nil
+	return pkResp.Result, nil
 }
 
-func VerifyPart(pk []byte, gammaG2 []byte, partDec []byte, url string) error {
-	client := http.Client{}
-
+func VerifyPart(pk []byte, gammaG2 []byte, part *api.PartDecResponse, url string) error {
 	req := &api.VerifyPartRequest{
-		Pk:      []byte(pk),
-		GammaG2: []byte(gammaG2),
-		PartDec: []byte(partDec),
-	}
-	data, err := proto.Marshal(req)
-	if err != nil {
-		return err
-	}
-
-	postReader := bytes.NewReader(data)
-
-	resp, err := client.Post(url, "application/protobuf", postReader)
-	if err != nil {
-		return err
+		Pk:      pk,
+		GammaG2: gammaG2,
+		PartDec: part.Result,
+		Proof:   part.Proof,
 	}
-	if resp.StatusCode != 200 {
-		return err
-	}
-	resp.Body.Close()
 
-	return nil
+	var verifyResp api.Response
+	return Post(url, req, &verifyResp)
 }
 
 func main() {
@@ -237,42 +181,51 @@ func main() {
 		j++
 	}
 
-	enc, err := EncryptTransaction([]byte(expected), pks, t, n, "http://127.0.0.1:8080/encrypt")
+	// every server checks the proofs of the ciphertexts against the registered committee, named
+	// after its hash so that the keys of another run don't clash with it
+	var committeeId string
+	j = 8080
+	for i := 0; i < int(k); i++ {
+		committee, err := RegisterCommittee("", pks, n, fmt.Sprintf("http://127.0.0.1:%d/register", j))
+		if err != nil {
+			fmt.Println("can't register the committee", err)
+			os.Exit(1)
+		}
+		committeeId = committee.Id
+		j++
+	}
 
+	enc, err := EncryptTransaction([]byte(expected), committeeId, t, "http://127.0.0.1:8080/encrypt")
 	if err != nil {
-		fmt.Println("can't encrypt transaction")
+		fmt.Println("can't encrypt transaction", err)
 		os.Exit(1)
 	}
 
-	parts := make([][]byte, n)
+	parts := make(map[uint64]([]byte))
 	j = 8080
 
 	for i := 0; i < int(k); i++ {
-		part, err := PartialDecrypt(enc.GammaG2, fmt.Sprintf("http://127.0.0.1:%d/partdec", j))
+		part, err := PartialDecrypt(enc, committeeId, t, fmt.Sprintf("http://127.0.0.1:%d/partdec", j))
 		if err != nil {
-			fmt.Println("can't get part")
+			fmt.Println("can't get part", err)
 			os.Exit(1)
 		}
 		err = VerifyPart(pks[i], enc.GammaG2, part, "http://127.0.0.1:8080/verifydec")
 		if err != nil {
-			fmt.Println("can't verify part")
+			fmt.Println("can't verify part", err)
 			os.Exit(1)
 		}
-		parts[i] = part
+		parts[uint64(i)] = part.Result
 		j++
 	}
 
-	new_parts := make(map[uint64]([]byte))
-	new_parts[0] = parts[0]
-
-	dec, err := DecryptTransaction(enc.Enc, pks, new_parts, enc.GammaG2, enc.Sa1, enc.Sa2, enc.Iv, t, n)
-
+	dec, err := DecryptTransaction(enc.Envelope, committeeId, parts, "http://127.0.0.1:8080/decrypt")
 	if err != nil {
-		fmt.Println("can't decrypt transaction")
+		fmt.Println("can't decrypt transaction", err)
 		os.Exit(1)
 	}
 
-	if string(dec) != expected {
+	if string(dec.Result) != expected {
 		fmt.Println("can't decrypt the data")
 		os.Exit(1)
 	}